@@ -0,0 +1,115 @@
+use crate::constants::{menu, notify};
+use crate::notify::{Alert, AlertSource};
+use crate::WindowKind;
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Whether alerts can carry buttons here. Elsewhere they are shown as plain notifications.
+pub const SUPPORTED: bool = cfg!(unix);
+
+/// Alerts currently waiting for a click, see [`reserve`]
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+/// One of the limited slots for an alert waiting for a click; freed when dropped
+pub struct Slot(());
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        WAITING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A slot to wait for a click on an alert's buttons in, or `None` while
+/// [`MAX_ALERTS_WITH_BUTTONS`](notify::MAX_ALERTS_WITH_BUTTONS) alerts are already waiting, as
+/// unanswered alerts would otherwise pile up blocked threads
+pub fn reserve() -> Option<Slot> {
+    WAITING
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < notify::MAX_ALERTS_WITH_BUTTONS).then_some(n + 1))
+        .ok()
+        .map(|_| Slot(()))
+}
+
+/// Buttons offered on an alert, as (action ID, label). The IDs are handled like tray menu IDs.
+pub fn for_alert(alert: &Alert) -> Vec<(String, &'static str)> {
+    let mut actions = vec![(menu::OPEN_CLAUDE.to_string(), "Open Claude")];
+    if let Some(source) = &alert.source {
+        actions.push((snooze_id(source), "Snooze 1h"));
+    }
+    actions.push((menu::REFRESH.to_string(), "Refresh now"));
+    actions
+}
+
+/// Action ID that snoozes the window `source` is about
+pub fn snooze_id(source: &AlertSource) -> String {
+    let window = match source.window {
+        WindowKind::FiveHour => "fiveHour",
+        WindowKind::SevenDay => "sevenDay",
+    };
+    format!("{}{window}:{}", menu::SNOOZE_PREFIX, source.profile)
+}
+
+/// Inverse of [`snooze_id`]
+pub fn parse_snooze(id: &str) -> Option<AlertSource> {
+    let (window, profile) = id.strip_prefix(menu::SNOOZE_PREFIX)?.split_once(':')?;
+    let window = match window {
+        "fiveHour" => WindowKind::FiveHour,
+        "sevenDay" => WindowKind::SevenDay,
+        _ => return None,
+    };
+    Some(AlertSource {
+        profile: profile.to_string(),
+        window,
+    })
+}
+
+/// Show `alert` with its buttons and block until it is dismissed; returns the action ID clicked
+#[cfg(target_os = "macos")]
+pub fn show(alert: &Alert) -> Result<Option<String>, Box<dyn Error>> {
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+
+    let actions = for_alert(alert);
+    let labels: Vec<&str> = actions.iter().map(|(_, label)| *label).collect();
+    let response = Notification::default()
+        .title(&alert.title)
+        .message(&alert.body)
+        .main_button(MainButton::DropdownActions("Actions", &labels))
+        .close_button("Dismiss")
+        .wait_for_click(true)
+        .send()?;
+
+    Ok(match response {
+        NotificationResponse::ActionButton(clicked) => {
+            actions.into_iter().find(|(_, label)| *label == clicked).map(|(id, _)| id)
+        }
+        _ => None,
+    })
+}
+
+/// Show `alert` with its buttons and block until it is dismissed; returns the action ID clicked
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn show(alert: &Alert) -> Result<Option<String>, Box<dyn Error>> {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .summary(&alert.title)
+        .body(&alert.body)
+        .auto_icon()
+        .timeout(notify_rust::Timeout::Milliseconds(notify::ALERT_BUTTONS_TIMEOUT_SECS * 1000));
+    for (id, label) in for_alert(alert) {
+        notification.action(&id, label);
+    }
+
+    let mut clicked = None;
+    notification.show()?.wait_for_action(|action| {
+        // "__closed" when dismissed or expired, "default" when the notification itself is clicked
+        if action.starts_with(menu::SNOOZE_PREFIX) || action == menu::OPEN_CLAUDE || action == menu::REFRESH {
+            clicked = Some(action.to_string());
+        }
+    });
+    Ok(clicked)
+}
+
+/// Never called, see [`SUPPORTED`]
+#[cfg(not(unix))]
+pub fn show(_alert: &Alert) -> Result<Option<String>, Box<dyn Error>> {
+    Ok(None)
+}
@@ -0,0 +1,314 @@
+use crate::constants::{self, api};
+use crate::history::UsageSample;
+use crate::paths;
+use crate::UsageData;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::{JoinHandle, JoinSet};
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+/// Local HTTP API settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Also serve `GET /metrics` for Prometheus
+    pub metrics: bool,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: api::DEFAULT_PORT,
+            metrics: false,
+        }
+    }
+}
+
+/// What the API serves; the app implements it on its state
+pub trait ApiSource: Send + Sync + 'static {
+    /// Latest usage per profile name
+    fn usage(&self) -> impl Future<Output = HashMap<String, UsageData>> + Send;
+
+    fn history(
+        &self,
+        profile: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageSample>, String>;
+
+    /// Refresh all profiles as soon as possible
+    fn refresh(&self);
+
+    /// Receives the active profile's usage after each refresh that fetched it, from now on
+    fn subscribe(&self) -> broadcast::Receiver<UsageData>;
+
+    /// `OpenMetrics` exposition, or `None` while the exporter is off
+    fn metrics(&self) -> impl Future<Output = Option<String>> + Send;
+}
+
+/// Sender for [`ApiSource::subscribe`]
+pub fn usage_events() -> broadcast::Sender<UsageData> {
+    broadcast::channel(api::EVENT_BUFFER).0
+}
+
+/// The token clients must send, from `api-token` in the config directory; created on first use
+pub fn load_or_create_token() -> Result<String, Box<dyn Error>> {
+    let path = paths::config_file(constants::API_TOKEN_FILE);
+    if let Ok(token) = fs::read_to_string(&path) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let mut bytes = [0u8; api::TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    let token = bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    });
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, &token)?;
+
+    #[cfg(unix)]
+    {
+        let mut perms = fs::metadata(&path)?.permissions();
+        perms.set_mode(constants::SECURE_FILE_MODE);
+        fs::set_permissions(&path, perms)?;
+    }
+
+    Ok(token)
+}
+
+/// The API listening on a loopback port; stops when dropped
+pub struct ApiServer {
+    port: u16,
+    task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    /// Listen on `127.0.0.1:port` (0 picks a free port), answering requests that carry `token`
+    pub async fn start<S: ApiSource>(port: u16, token: String, source: Arc<S>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        let port = listener.local_addr()?.port();
+        let token: Arc<str> = token.into();
+
+        let task = tokio::spawn(async move {
+            // Owned by this task, so aborting it also closes open connections such as event streams
+            let mut connections = JoinSet::new();
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => {
+                        let Ok((stream, _)) = accepted else { break };
+                        let source = source.clone();
+                        let token = token.clone();
+                        connections.spawn(async move {
+                            let _ = handle(stream, &token, source.as_ref()).await;
+                        });
+                    }
+                    Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                }
+            }
+        });
+
+        Ok(Self { port, task })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The token from `Authorization: Bearer`, or from `?token=` for clients such as
+    /// `EventSource` that can't set headers
+    fn token(&self) -> Option<&str> {
+        self.header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or_else(|| self.query.get("token").map(String::as_str))
+    }
+}
+
+/// Read the request line and headers; the body is never needed
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Request>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        if buf.len() > api::MAX_HEAD_BYTES {
+            return Ok(None);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let Ok(url) = reqwest::Url::parse(&format!("http://localhost{}", request_line.next().unwrap_or("/"))) else {
+        return Ok(None);
+    };
+    let headers = lines
+        .map_while(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(Some(Request {
+        method,
+        path: url.path().to_string(),
+        query: url.query_pairs().into_owned().collect(),
+        headers,
+    }))
+}
+
+async fn handle<S: ApiSource>(mut stream: TcpStream, token: &str, source: &S) -> std::io::Result<()> {
+    let read = tokio::time::timeout(Duration::from_secs(api::READ_TIMEOUT_SECS), read_request(&mut stream));
+    let Ok(Ok(Some(request))) = read.await else {
+        return Ok(());
+    };
+
+    if !request.token().is_some_and(|given| same_token(given, token)) {
+        return respond(stream, 401, &json!({ "error": "missing or wrong token" })).await;
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/usage") => respond(stream, 200, &json!(source.usage().await)).await,
+        ("GET", "/history") => {
+            let (status, body) = history(&request, source);
+            respond(stream, status, &body).await
+        }
+        ("POST", "/refresh") => {
+            source.refresh();
+            respond(stream, 202, &json!({ "refreshing": true })).await
+        }
+        ("GET", "/events") => stream_events(stream, source.subscribe()).await,
+        ("GET", "/metrics") => match source.metrics().await {
+            Some(text) => send(stream, 200, constants::metrics::CONTENT_TYPE, &text).await,
+            None => respond(stream, 404, &json!({ "error": "metrics are turned off" })).await,
+        },
+        (_, "/usage" | "/history" | "/refresh" | "/events" | "/metrics") => {
+            respond(stream, 405, &json!({ "error": "method not allowed" })).await
+        }
+        _ => respond(stream, 404, &json!({ "error": "not found" })).await,
+    }
+}
+
+/// `GET /history?profile=&from=&to=`, with the same meaning as the `get_usage_history` command
+fn history<S: ApiSource>(request: &Request, source: &S) -> (u16, serde_json::Value) {
+    let mut range = [None, None];
+    for (bound, key) in range.iter_mut().zip(["from", "to"]) {
+        if let Some(value) = request.query.get(key) {
+            match DateTime::parse_from_rfc3339(value) {
+                Ok(time) => *bound = Some(time.with_timezone(&Utc)),
+                Err(e) => return (400, json!({ "error": format!("{key}: {e}") })),
+            }
+        }
+    }
+
+    let profile = request.query.get("profile").map(String::as_str);
+    match source.history(profile, range[0], range[1]) {
+        Ok(samples) => (200, json!(samples)),
+        Err(e) => (500, json!({ "error": e })),
+    }
+}
+
+/// Server-Sent `usage-updated` events with each fetched usage, until the client goes away
+async fn stream_events(mut stream: TcpStream, mut events: broadcast::Receiver<UsageData>) -> std::io::Result<()> {
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\
+              Connection: keep-alive\r\n\r\n: connected\n\n",
+        )
+        .await?;
+
+    let mut keepalive = tokio::time::interval(Duration::from_secs(api::KEEPALIVE_SECS));
+    keepalive.tick().await;
+    loop {
+        let chunk = tokio::select! {
+            event = events.recv() => match event {
+                Ok(usage) => format!("event: usage-updated\ndata: {}\n\n", serde_json::to_string(&usage)?),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            _ = keepalive.tick() => ": keepalive\n\n".to_string(),
+        };
+        stream.write_all(chunk.as_bytes()).await?;
+    }
+}
+
+async fn respond(stream: TcpStream, status: u16, body: &serde_json::Value) -> std::io::Result<()> {
+    send(stream, status, "application/json", &body.to_string()).await
+}
+
+async fn send(mut stream: TcpStream, status: u16, content_type: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Compares without returning early, so response times don't reveal how much of a guess was right
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::Utc;
+use seekers_lib::claude;
+use seekers_lib::credentials::CredentialsManager;
+use seekers_lib::display::{format_reset_time, format_time_until, format_tray_title, make_progress_bar};
+use seekers_lib::forecast;
+use seekers_lib::history::{HistoryStore, UsageSample};
+use seekers_lib::paths;
+use seekers_lib::settings::{AppSettings, SettingsManager};
+use seekers_lib::{UsageData, UsageWindow};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: seekers-cli [--format <table|json|prompt>] [--profile <NAME>] [--config-dir <DIR>]
+
+Print current Claude usage using the credentials and settings of the Seekers app.
+
+Options:
+  -f, --format <FORMAT>  Output format [default: table]
+                           table   human-readable table
+                           json    machine-readable JSON
+                           prompt  single-line segment for shell prompts
+  -p, --profile <NAME>   Profile to query [default: the active profile]
+      --config-dir <DIR> Read config from DIR instead of the default location
+  -h, --help             Print this help";
+
+enum Format {
+    Table,
+    Json,
+    Prompt,
+}
+
+struct Options {
+    format: Format,
+    profile: Option<String>,
+    config_dir: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        format: Format::Table,
+        profile: None,
+        config_dir: None,
+    };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = || inline.clone().or_else(|| args.next()).ok_or(format!("{flag} requires a value"));
+
+        match flag.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "prompt" => Format::Prompt,
+                    other => return Err(format!("unknown format '{other}'")),
+                };
+            }
+            "-p" | "--profile" => options.profile = Some(value()?),
+            "--config-dir" => options.config_dir = Some(value()?.into()),
+            other => return Err(format!("unexpected argument '{other}'")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn print_table(usage: &UsageData, settings: &AppSettings) {
+    let rows = [("Session", usage.five_hour.as_ref()), ("Weekly", usage.seven_day.as_ref())];
+
+    for (label, window) in rows {
+        let Some(window) = window else {
+            continue;
+        };
+        print_row(label, window, settings);
+    }
+}
+
+fn print_row(label: &str, window: &UsageWindow, settings: &AppSettings) {
+    let pct = window.utilization.round() as i32;
+    let bar = make_progress_bar(window.utilization, settings);
+    println!("{label:<8} {bar} {pct:>3}%  ↻ {}", format_reset_time(&window.resets_at));
+
+    if let Some(limit_at) = window.forecast.as_ref().and_then(|f| f.limit_at) {
+        println!("{:<8} ⚠ at this pace: limit {}", "", format_time_until(limit_at));
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("seekers-cli: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    paths::init(options.config_dir.clone());
+    let settings = SettingsManager::new().load().unwrap_or_default();
+    let profiles = match CredentialsManager::new().load_profiles() {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            eprintln!("seekers-cli: failed to load credentials: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let profile = match options.profile {
+        Some(ref name) => profiles.get(name),
+        None => profiles.active_profile(),
+    };
+    let Some(profile) = profile.filter(|p| p.is_configured()) else {
+        match options.profile {
+            Some(name) => eprintln!("seekers-cli: Profile '{name}' not configured"),
+            None => eprintln!("seekers-cli: Credentials not configured"),
+        }
+        return ExitCode::FAILURE;
+    };
+
+    let client = reqwest::Client::new();
+    let base_url = claude::api_base(settings.api_base_url.as_deref());
+    let mut usage = match claude::fetch_usage(&base_url, &profile.org_id, &profile.session_key, &client).await {
+        Ok(usage) => usage,
+        Err(e) => {
+            eprintln!("seekers-cli: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Project from the app's history plus this fetch, without writing to the store.
+    let now = Utc::now();
+    let history = HistoryStore::new().query(Some(&profile.name), Some(forecast::history_since(now)), None);
+    if let Ok(mut samples) = history {
+        samples.push(UsageSample::from_usage(&profile.name, &usage, now));
+        forecast::apply(&mut usage, &samples);
+    }
+
+    match options.format {
+        Format::Table => print_table(&usage, &settings),
+        Format::Json => match serde_json::to_string_pretty(&usage) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("seekers-cli: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Format::Prompt => println!("{}", format_tray_title(&usage, &settings)),
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,72 @@
+use seekers_lib::mock::{MockResponse, MockServer};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: seekers-mock [--port <PORT>] [SCENARIO...]
+
+Serve scripted claude.ai usage responses on 127.0.0.1. Scenarios are answered in order and
+the last one repeats. Run the app with SEEKERS_API_BASE set to the printed URL.
+
+Scenarios:
+  normal            42% session, 18% weekly (default)
+  high              91% session, 64% weekly
+  missing-windows   no five_hour/seven_day objects
+  null-resets-at    windows without a reset time
+  unauthorized      401
+  forbidden         403
+  rate-limited      429 with Retry-After: 120
+  server-error      500
+  malformed         invalid JSON body
+  login-redirect    302 to /login
+
+Options:
+  -p, --port <PORT>  Port to listen on [default: 8787]
+  -h, --help         Print this help";
+
+fn parse_args() -> Result<(u16, Vec<MockResponse>), String> {
+    let mut port = 8787;
+    let mut script = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            "-p" | "--port" => {
+                let value = args.next().ok_or("--port requires a value")?;
+                port = value.parse().map_err(|_| format!("invalid port '{value}'"))?;
+            }
+            name => script.push(MockResponse::from_name(name).ok_or(format!("unknown scenario '{name}'"))?),
+        }
+    }
+
+    if script.is_empty() {
+        script.push(MockResponse::usage(42.0, 18.0));
+    }
+    Ok((port, script))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let (port, script) = match parse_args() {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("seekers-mock: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let server = match MockServer::bind(port, script).await {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("seekers-mock: failed to listen on port {port}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("SEEKERS_API_BASE={}", server.base_url());
+    let _ = tokio::signal::ctrl_c().await;
+    ExitCode::SUCCESS
+}
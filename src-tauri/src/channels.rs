@@ -0,0 +1,258 @@
+use crate::claude::parse_retry_after;
+use crate::constants::channels;
+use crate::error::ChannelErrorEvent;
+use crate::notify::Alert;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+/// Kind of endpoint a channel posts to, which decides the payload format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelKind {
+    /// JSON `{"title", "body", "source"}` to any URL
+    Webhook,
+    /// Slack incoming webhook, `{"text"}`; Mattermost and Discord's `/slack` URLs accept it too
+    Slack,
+    /// Discord webhook, `{"content"}`
+    Discord,
+    /// ntfy topic URL; the body is sent as plain text
+    Ntfy,
+    /// Gotify `/message` URL, authenticated with an application token
+    Gotify,
+}
+
+/// One notification channel from settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelConfig {
+    /// Stable identifier the URL and token are stored under
+    pub id: String,
+    /// Shown in delivery errors
+    pub name: String,
+    pub enabled: bool,
+    pub kind: ChannelKind,
+    /// Kept in the credential store rather than `settings.json`, like `token`
+    pub url: String,
+    /// Access token for ntfy, application token for Gotify
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Title template; `{title}` and `{body}` are filled in. Empty sends the alert's title.
+    pub title: String,
+    /// Body template, like `title`. Empty sends the alert's body.
+    pub message: String,
+    /// Extra attempts after a failed delivery, each waiting twice as long as the one before
+    pub retries: u32,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: "Webhook".to_string(),
+            enabled: true,
+            kind: ChannelKind::Webhook,
+            url: String::new(),
+            token: None,
+            title: String::new(),
+            message: String::new(),
+            retries: channels::DEFAULT_RETRIES,
+        }
+    }
+}
+
+impl ChannelConfig {
+    pub fn channel(&self) -> Box<dyn Channel + Send + Sync> {
+        let url = self.url.trim().to_string();
+        let token = self.token.clone().filter(|t| !t.trim().is_empty());
+        match self.kind {
+            ChannelKind::Webhook => Box::new(Webhook { url }),
+            ChannelKind::Slack => Box::new(Slack { url }),
+            ChannelKind::Discord => Box::new(Discord { url }),
+            ChannelKind::Ntfy => Box::new(Ntfy { url, token }),
+            ChannelKind::Gotify => Box::new(Gotify { url, token }),
+        }
+    }
+
+    /// The alert as this channel sends it, with its templates applied
+    pub fn render(&self, alert: &Alert) -> Alert {
+        let fill = |template: &str, fallback: &str| {
+            if template.trim().is_empty() {
+                fallback.to_string()
+            } else {
+                template.replace("{title}", &alert.title).replace("{body}", &alert.body)
+            }
+        };
+        Alert {
+            title: fill(&self.title, &alert.title),
+            body: fill(&self.message, &alert.body),
+            source: alert.source.clone(),
+        }
+    }
+}
+
+/// The parts of a [`ChannelConfig`] that grant access to the endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelSecret {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Somewhere alerts can be pushed to besides the desktop notification
+pub trait Channel {
+    /// Request that delivers `alert`, already rendered with the channel's templates
+    fn request(&self, client: &Client, alert: &Alert) -> RequestBuilder;
+}
+
+pub struct Webhook {
+    pub url: String,
+}
+
+impl Channel for Webhook {
+    fn request(&self, client: &Client, alert: &Alert) -> RequestBuilder {
+        client.post(&self.url).json(&json!({
+            "title": alert.title,
+            "body": alert.body,
+            "source": channels::WEBHOOK_SOURCE,
+        }))
+    }
+}
+
+pub struct Slack {
+    pub url: String,
+}
+
+impl Channel for Slack {
+    fn request(&self, client: &Client, alert: &Alert) -> RequestBuilder {
+        client
+            .post(&self.url)
+            .json(&json!({ "text": format!("*{}*\n{}", alert.title, alert.body) }))
+    }
+}
+
+pub struct Discord {
+    pub url: String,
+}
+
+impl Channel for Discord {
+    fn request(&self, client: &Client, alert: &Alert) -> RequestBuilder {
+        client
+            .post(&self.url)
+            .json(&json!({ "content": format!("**{}**\n{}", alert.title, alert.body) }))
+    }
+}
+
+pub struct Ntfy {
+    pub url: String,
+    pub token: Option<String>,
+}
+
+impl Channel for Ntfy {
+    fn request(&self, client: &Client, alert: &Alert) -> RequestBuilder {
+        // The title goes in the query rather than the `Title` header, which can't carry UTF-8
+        let request = client
+            .post(&self.url)
+            .query(&[("title", &alert.title)])
+            .body(alert.body.clone());
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+pub struct Gotify {
+    pub url: String,
+    pub token: Option<String>,
+}
+
+impl Channel for Gotify {
+    fn request(&self, client: &Client, alert: &Alert) -> RequestBuilder {
+        let request = client.post(&self.url).json(&json!({
+            "title": alert.title,
+            "message": alert.body,
+            "priority": channels::GOTIFY_PRIORITY,
+        }));
+        match &self.token {
+            Some(token) => request.header("X-Gotify-Key", token),
+            None => request,
+        }
+    }
+}
+
+/// Network errors, timeouts, 429 and 5xx may go away on their own; anything else won't
+fn retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Send `alert` through `config`, retrying transient failures after `delay`, then twice that, ...
+/// A rate limit's `Retry-After` is waited out instead, unless it is too long to be worth it.
+pub async fn deliver(client: &Client, config: &ChannelConfig, alert: &Alert, mut delay: Duration) -> Result<(), String> {
+    let channel = config.channel();
+    let alert = config.render(alert);
+    let retries = config.retries.min(channels::MAX_RETRIES);
+
+    let mut attempt = 0;
+    loop {
+        let request = channel
+            .request(client, &alert)
+            .timeout(Duration::from_secs(channels::TIMEOUT_SECS));
+        let (error, retry_after) = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if !retryable(response.status()) => return Err(format!("HTTP {}", response.status())),
+            Ok(response) => (format!("HTTP {}", response.status()), parse_retry_after(response.headers())),
+            Err(e) if e.is_builder() => return Err(e.to_string()),
+            Err(e) => (e.to_string(), None),
+        };
+
+        if attempt >= retries {
+            return Err(error);
+        }
+        let wait = match retry_after {
+            Some(secs) if secs > channels::MAX_RETRY_AFTER_SECS => {
+                return Err(format!("{error}, asked to retry after {secs}s"));
+            }
+            Some(secs) => Duration::from_secs(secs).max(delay),
+            None => delay,
+        };
+        attempt += 1;
+        tokio::time::sleep(wait).await;
+        delay *= 2;
+    }
+}
+
+/// Deliver every alert to every enabled channel, returning the deliveries that failed for good.
+///
+/// Channels are sent to at the same time, so one that is slow or retrying doesn't hold up the
+/// rest; each channel gets the alerts in order.
+pub async fn dispatch(client: &Client, configs: &[ChannelConfig], alerts: &[Alert]) -> Vec<ChannelErrorEvent> {
+    let delay = Duration::from_millis(channels::RETRY_DELAY_MS);
+    let tasks: Vec<_> = configs
+        .iter()
+        .filter(|c| c.enabled && !c.url.trim().is_empty())
+        .map(|config| {
+            let (client, config, alerts) = (client.clone(), config.clone(), alerts.to_vec());
+            tokio::spawn(async move {
+                let mut failures = Vec::new();
+                for alert in &alerts {
+                    if let Err(message) = deliver(&client, &config, alert, delay).await {
+                        failures.push(ChannelErrorEvent {
+                            channel: config.name.clone(),
+                            message,
+                        });
+                    }
+                }
+                failures
+            })
+        })
+        .collect();
+
+    let mut failures = Vec::new();
+    for task in tasks {
+        failures.extend(task.await.unwrap_or_default());
+    }
+    failures
+}
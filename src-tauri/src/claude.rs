@@ -1,6 +1,8 @@
 use crate::constants;
+use crate::error::FetchError;
 use crate::{UsageData, UsageWindow};
-use serde::Deserialize;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 struct ClaudeUsageResponse {
@@ -14,35 +16,129 @@ struct ClaudeUsageWindow {
     resets_at: Option<String>,
 }
 
-pub async fn fetch_usage(org_id: &str, session_key: &str, client: &reqwest::Client) -> Result<UsageData, Box<dyn std::error::Error + Send + Sync>> {
+#[derive(Debug, Deserialize)]
+struct ClaudeOrganization {
+    uuid: String,
+    name: String,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    rate_limit_tier: Option<String>,
+}
+
+/// An organization the session key has access to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub uuid: String,
+    pub name: String,
+    /// Human-readable plan, e.g. "Pro" or "Max 20x"
+    pub plan: String,
+}
+
+impl From<ClaudeOrganization> for Organization {
+    fn from(org: ClaudeOrganization) -> Self {
+        let plan = plan_tier(&org);
+        Self {
+            uuid: org.uuid,
+            name: org.name,
+            plan,
+        }
+    }
+}
+
+fn plan_tier(org: &ClaudeOrganization) -> String {
+    let tier = org.rate_limit_tier.as_deref().unwrap_or_default();
+    let has = |capability: &str| org.capabilities.iter().any(|c| c == capability);
+
+    let plan = if tier.ends_with("max_20x") {
+        "Max 20x"
+    } else if tier.ends_with("max_5x") {
+        "Max 5x"
+    } else if has("claude_max") {
+        "Max"
+    } else if has("claude_pro") {
+        "Pro"
+    } else if has("raven") {
+        "Team"
+    } else if has("api") && !has("chat") {
+        "API"
+    } else {
+        "Free"
+    };
+    plan.to_string()
+}
 
-    let url = format!("{}/organizations/{}/usage", constants::CLAUDE_API_BASE, org_id);
+/// API base URL: `SEEKERS_API_BASE` if set, then the configured override, then claude.ai
+pub fn api_base(configured: Option<&str>) -> String {
+    let base = std::env::var(constants::API_BASE_ENV)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .or_else(|| configured.filter(|v| !v.trim().is_empty()).map(str::to_string))
+        .unwrap_or_else(|| constants::CLAUDE_API_BASE.to_string());
+    base.trim().trim_end_matches('/').to_string()
+}
 
+/// GET `url` with the session cookie and return the body of a successful response
+async fn get(url: &str, session_key: &str, client: &reqwest::Client) -> Result<String, FetchError> {
     let response = client
-        .get(&url)
+        .get(url)
         .header("Cookie", format!("sessionKey={session_key}"))
         .header("Accept", "application/json")
         .header("User-Agent", constants::USER_AGENT)
         .send()
         .await?;
 
+    // A rejected cookie can also show up as a redirect to the login page
+    if response.url().path().contains(constants::LOGIN_PATH) {
+        return Err(FetchError::SessionExpired);
+    }
+
     if !response.status().is_success() {
-        let status = response.status();
-        return Err(format!("API request failed: {status}").into());
+        let retry_after = parse_retry_after(response.headers());
+        return Err(FetchError::from_status(response.status(), retry_after));
     }
 
-    let data: ClaudeUsageResponse = response.json().await?;
+    Ok(response.text().await?)
+}
+
+pub async fn fetch_usage(base_url: &str, org_id: &str, session_key: &str, client: &reqwest::Client) -> Result<UsageData, FetchError> {
+    let url = format!("{base_url}/organizations/{org_id}/usage");
+    let body = get(&url, session_key, client).await?;
+    let data: ClaudeUsageResponse =
+        serde_json::from_str(&body).map_err(|e| FetchError::SchemaChanged(e.to_string()))?;
 
     Ok(UsageData {
         five_hour: data.five_hour.map(|w| UsageWindow {
             utilization: w.utilization,
             resets_at: w.resets_at.unwrap_or_default(),
+            forecast: None,
         }),
         seven_day: data.seven_day.map(|w| UsageWindow {
             utilization: w.utilization,
             resets_at: w.resets_at.unwrap_or_default(),
+            forecast: None,
         }),
+        fetched_at: Some(Utc::now()),
     })
 }
 
+/// Organizations visible to `session_key`, so the org ID doesn't have to be copied by hand
+pub async fn fetch_organizations(base_url: &str, session_key: &str, client: &reqwest::Client) -> Result<Vec<Organization>, FetchError> {
+    let body = get(&format!("{base_url}/organizations"), session_key, client).await?;
+    let orgs: Vec<ClaudeOrganization> =
+        serde_json::from_str(&body).map_err(|e| FetchError::SchemaChanged(e.to_string()))?;
 
+    Ok(orgs.into_iter().map(Organization::from).collect())
+}
+
+/// `Retry-After` as seconds, accepting both the delta and the HTTP-date form
+pub fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = date.signed_duration_since(chrono::Utc::now()).num_seconds();
+    Some(secs.max(0) as u64)
+}
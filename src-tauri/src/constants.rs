@@ -4,18 +4,67 @@ pub const USER_AGENT: &str = concat!("Seekers/", env!("CARGO_PKG_VERSION"));
 /// Claude API base URL
 pub const CLAUDE_API_BASE: &str = "https://claude.ai/api";
 
+/// Environment variable that overrides the API base URL (e.g. to point at `seekers-mock`)
+pub const API_BASE_ENV: &str = "SEEKERS_API_BASE";
+
+/// Path segment of the login page claude.ai redirects to when the session key is rejected
+pub const LOGIN_PATH: &str = "/login";
+
 /// Claude website URL
 pub const CLAUDE_URL: &str = "https://claude.ai";
 
-/// Config directory name (under ~/.config/)
+/// Directory name under the XDG config and data homes
 pub const CONFIG_DIR_NAME: &str = "seekers";
 
+/// Environment variable that puts all of this instance's files in one directory
+pub const CONFIG_DIR_ENV: &str = "SEEKERS_CONFIG_DIR";
+
 /// Credentials filename
 pub const CREDENTIALS_FILE: &str = "credentials.json";
 
+/// Keyring service and account under which the profile set is stored
+pub const KEYRING_SERVICE: &str = "seekers";
+pub const KEYRING_ACCOUNT: &str = "credentials";
+
+/// Keyring account under which notification channel URLs and tokens are stored
+pub const KEYRING_CHANNELS_ACCOUNT: &str = "channels";
+
+/// Channel URLs and tokens when the file store is used, beside `credentials.json`
+pub const CHANNEL_SECRETS_FILE: &str = "channel-secrets.json";
+
+/// Environment variable that selects the credential store (`keyring` or `file`)
+pub const CREDENTIAL_STORE_ENV: &str = "SEEKERS_CREDENTIAL_STORE";
+
+/// Passphrase that encrypts `credentials.json` when the file store is used
+pub const CREDENTIALS_PASSPHRASE_ENV: &str = "SEEKERS_CREDENTIALS_PASSPHRASE";
+
+/// Key file that encrypts `credentials.json` when the file store is used
+pub const CREDENTIALS_KEY_FILE_ENV: &str = "SEEKERS_CREDENTIALS_KEY_FILE";
+
+/// Format version written by the encrypted credentials file; plaintext files are version 1
+pub const ENCRYPTED_CREDENTIALS_VERSION: u32 = 2;
+
+/// Associated data bound into every encrypted credentials file
+pub const CREDENTIALS_AAD: &[u8] = b"seekers-credentials";
+
 /// Settings filename
 pub const SETTINGS_FILE: &str = "settings.json";
 
+/// Extension of the copy kept when `settings.json` can't be parsed
+pub const SETTINGS_BACKUP_EXTENSION: &str = "json.bak";
+
+/// Usage history filename (JSON lines, one sample per fetch)
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// Notification dedup state, so restarts don't repeat alerts
+pub const NOTIFY_STATE_FILE: &str = "notifications.json";
+
+/// Token local API clients authenticate with, in the config directory
+pub const API_TOKEN_FILE: &str = "api-token";
+
+/// Name given to the profile migrated from a single-account credentials file
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
 /// File permissions for credentials (owner read/write only)
 #[cfg(unix)]
 pub const SECURE_FILE_MODE: u32 = 0o600;
@@ -26,16 +75,38 @@ pub const TRAY_ID: &str = "main-tray";
 /// Default tray title when no data
 pub const TRAY_TITLE_DEFAULT: &str = "--%";
 
+/// Tray title while the shown profile's session key has been rejected
+pub const TRAY_TITLE_AUTH_NEEDED: &str = "🔑 auth needed";
+
+/// Prefix for the tray title and menu line when the last fetch failed
+pub const TRAY_ERROR_MARKER: &str = "⚠";
+
+/// Suffix for the tray title while the shown numbers are stale, with `StaleMarker::Question`
+pub const TRAY_STALE_MARKER: &str = "?";
+
 /// Menu item IDs
 pub mod menu {
     pub const OPEN_CLAUDE: &str = "open-claude";
     pub const REFRESH: &str = "refresh";
     pub const SETTINGS: &str = "settings";
     pub const QUIT: &str = "quit";
+
+    /// Prefix of the "Switch Profile" entries, followed by the profile name
+    pub const PROFILE_PREFIX: &str = "profile:";
+    pub const PROFILE_AGGREGATE: &str = "profile-aggregate";
+
+    /// Prefix of the Snooze action on alerts, followed by the window and the profile name
+    pub const SNOOZE_PREFIX: &str = "snooze:";
 }
 
 /// Time constants
 pub mod time {
+    /// How often the config files are checked for external edits
+    pub const CONFIG_WATCH_INTERVAL_SECS: u64 = 2;
+
+    /// How often the end of quiet hours is checked, to send the digest (seconds)
+    pub const QUIET_HOURS_CHECK_SECS: u64 = 60;
+
     /// Seconds per minute
     pub const SECONDS_PER_MINUTE: u64 = 60;
 
@@ -47,9 +118,124 @@ pub mod time {
 
     /// Hours threshold for "tomorrow" display
     pub const HOURS_TOMORROW_THRESHOLD: i64 = 48;
+}
+
+/// When usage counts as stale
+pub mod stale {
+    /// Usage is never stale younger than this, so idle slowdowns and manual refresh don't
+    /// trip the marker (minutes)
+    pub const MIN_MINUTES: i64 = 30;
+}
+
+/// Suspend/resume and reconnect detection
+pub mod resume {
+    /// How often the clocks and the connection are checked (seconds)
+    pub const CHECK_INTERVAL_SECS: u64 = 10;
+
+    /// The wall clock running ahead of the monotonic clock by this much means the machine slept (seconds)
+    pub const CLOCK_JUMP_SECS: u64 = 30;
+
+    /// Give up on reaching claude.ai after this long (seconds)
+    pub const PROBE_TIMEOUT_SECS: u64 = 5;
+
+    /// The tray is re-rendered at least this often, so "Updated 3m ago" keeps counting (seconds)
+    pub const RERENDER_SECS: u64 = 60;
+}
+
+/// Notifications
+pub mod notify {
+    /// Alerts listed in a quiet-hours digest before the rest are only counted
+    pub const DIGEST_MAX_LINES: usize = 5;
+
+    /// How long the Snooze action on an alert silences its window (minutes)
+    pub const SNOOZE_MINUTES: i64 = 60;
+
+    /// Alerts with buttons each hold a thread until answered; past this many, alerts are shown without
+    pub const MAX_ALERTS_WITH_BUTTONS: usize = 3;
+
+    /// Alerts with buttons expire after this long on Linux, freeing their thread (seconds)
+    pub const ALERT_BUTTONS_TIMEOUT_SECS: u32 = 10 * 60;
+}
 
-    /// Fallback check interval when auto-refresh is disabled (seconds)
-    pub const DISABLED_REFRESH_CHECK_SECS: u64 = 60;
+/// Notification channels (webhooks, Slack, ntfy, ...)
+pub mod channels {
+    /// Extra attempts after a failed delivery, for new channels
+    pub const DEFAULT_RETRIES: u32 = 3;
+
+    /// Retries configured above this are capped, so a bad endpoint can't pile up deliveries
+    pub const MAX_RETRIES: u32 = 6;
+
+    /// Delay before the first retry; doubles for each one after it (milliseconds)
+    pub const RETRY_DELAY_MS: u64 = 1000;
+
+    /// A rate limit asking to wait longer than this fails the delivery instead (seconds)
+    pub const MAX_RETRY_AFTER_SECS: u64 = 5 * 60;
+
+    /// Give up on a single delivery attempt after this long (seconds)
+    pub const TIMEOUT_SECS: u64 = 10;
+
+    /// Priority of Gotify messages (0-10; 5 and up makes a sound on Android)
+    pub const GOTIFY_PRIORITY: u8 = 5;
+
+    /// `source` field of generic webhook payloads
+    pub const WEBHOOK_SOURCE: &str = "seekers";
+}
+
+/// Local HTTP API
+pub mod api {
+    /// Port the API listens on by default, on 127.0.0.1 only
+    pub const DEFAULT_PORT: u16 = 8788;
+
+    /// Random bytes in a new token, which is written out as hex
+    pub const TOKEN_BYTES: usize = 32;
+
+    /// Largest request head accepted before the connection is dropped (bytes)
+    pub const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+    /// A client that hasn't sent its request by then is dropped (seconds)
+    pub const READ_TIMEOUT_SECS: u64 = 10;
+
+    /// Event streams get a comment this often, so clients notice a dead connection (seconds)
+    pub const KEEPALIVE_SECS: u64 = 30;
+
+    /// Updates buffered for a slow event stream before it skips ahead
+    pub const EVENT_BUFFER: usize = 16;
+}
+
+/// Prometheus/OpenMetrics exporter
+pub mod metrics {
+    /// Upper bounds of the fetch latency histogram buckets (seconds)
+    pub const LATENCY_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+    /// `Content-Type` of `GET /metrics`
+    pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+}
+
+/// Usage history
+pub mod history {
+    /// Samples older than this are dropped from `history.jsonl` (days)
+    pub const RETENTION_DAYS: i64 = 90;
+
+    /// How often appending a sample also prunes old ones (hours)
+    pub const PRUNE_INTERVAL_HOURS: i64 = 24;
+
+    /// Extension of the file written before it replaces `history.jsonl`
+    pub const REWRITE_TEMP_EXTENSION: &str = "jsonl.tmp";
+}
+
+/// Burn-rate forecasting
+pub mod forecast {
+    /// How far back the 5-hour window's rate is measured (minutes)
+    pub const FIVE_HOUR_LOOKBACK_MINUTES: i64 = 60;
+
+    /// How far back the 7-day window's rate is measured (minutes)
+    pub const SEVEN_DAY_LOOKBACK_MINUTES: i64 = 24 * 60;
+
+    /// Minimum span between samples before a rate is reported (minutes)
+    pub const MIN_SPAN_MINUTES: i64 = 5;
+
+    /// Two `resets_at` values closer than this belong to the same cycle (seconds)
+    pub const RESET_TOLERANCE_SECS: i64 = 60;
 }
 
 /// Progress bar characters
@@ -59,3 +245,36 @@ pub mod progress {
     pub const BAR: (&str, &str) = ("█", "░");
     pub const DOTS: (&str, &str) = ("⬤", "○");
 }
+
+/// Auto-refresh pacing
+pub mod schedule {
+    /// Shortest delay between automatic refreshes, however fast usage moves (seconds)
+    pub const MIN_INTERVAL_SECS: u64 = 60;
+
+    /// Backoff stops growing at this delay, unless the configured interval is longer (seconds)
+    pub const MAX_BACKOFF_SECS: u64 = 60 * 60;
+
+    /// Cap on the backoff exponent, so the multiplication never overflows
+    pub const MAX_BACKOFF_DOUBLINGS: u32 = 6;
+
+    /// Any window climbing at least this fast speeds polling up (percentage points per hour)
+    pub const FAST_RATE_PER_HOUR: f64 = 20.0;
+
+    /// A window in use that resets within this many minutes speeds polling up
+    pub const RESET_SOON_MINUTES: i64 = 15;
+
+    /// The interval is divided by this while usage moves fast
+    pub const SPEEDUP_DIVISOR: u32 = 3;
+
+    /// Windows moving no faster than this count as idle (percentage points per hour)
+    pub const IDLE_RATE_PER_HOUR: f64 = 0.5;
+
+    /// The interval is multiplied by this while all windows are idle
+    pub const SLOWDOWN_FACTOR: u32 = 2;
+
+    /// Delay after a window's `resets_at` before refreshing, so claude.ai has rolled over (seconds)
+    pub const RESET_GRACE_SECS: u64 = 5;
+
+    /// Idle slowdown never stretches the interval past this (seconds)
+    pub const MAX_IDLE_INTERVAL_SECS: u64 = 60 * 60;
+}
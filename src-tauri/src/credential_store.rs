@@ -0,0 +1,290 @@
+use crate::constants;
+use crate::crypto::{self, FileKey};
+use crate::paths;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+/// Where the serialized profile set, or another secret, is kept
+pub trait CredentialStore: Send + Sync {
+    /// Short name shown to the user, e.g. "keyring"
+    fn name(&self) -> &'static str;
+
+    /// Stored contents, or `None` if nothing has been saved yet
+    fn read(&self) -> Result<Option<String>, Box<dyn Error>>;
+
+    fn write(&self, contents: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Remove the stored contents; succeeds if there were none
+    fn clear(&self) -> Result<(), Box<dyn Error>>;
+
+    /// Backing file, for stores that can be edited outside the app
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// File whose edits outside the app should be picked up: the backing file, or one that
+    /// [`import_watched`](Self::import_watched) takes in
+    fn watched_path(&self) -> Option<&Path> {
+        self.path()
+    }
+
+    /// Contents of the watched file, if it isn't the backing file and is waiting to be imported
+    fn pending_import(&self) -> Result<Option<String>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    /// Move the pending import into the store. Returns whether anything was imported.
+    fn import_watched(&self) -> Result<bool, Box<dyn Error>> {
+        Ok(false)
+    }
+}
+
+/// `credentials.json` in the config directory, readable only by the owner.
+///
+/// With a key the file is written encrypted; plaintext and encrypted files are both read.
+pub struct FileStore {
+    path: PathBuf,
+    key: Option<FileKey>,
+}
+
+impl FileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, key: None }
+    }
+
+    pub fn encrypted(path: PathBuf, key: FileKey) -> Self {
+        Self { path, key: Some(key) }
+    }
+
+    /// Rewrite a plaintext file in encrypted form. Returns whether anything changed.
+    pub fn encrypt_existing(&self) -> Result<bool, Box<dyn Error>> {
+        if self.key.is_none() || !self.path.exists() {
+            return Ok(false);
+        }
+        let raw = fs::read_to_string(&self.path)?;
+        if crypto::is_sealed(&raw) {
+            return Ok(false);
+        }
+        self.write(&raw)?;
+        Ok(true)
+    }
+
+    pub fn default_path() -> PathBuf {
+        paths::config_file(constants::CREDENTIALS_FILE)
+    }
+}
+
+impl CredentialStore for FileStore {
+    fn name(&self) -> &'static str {
+        if self.key.is_some() {
+            "encrypted file"
+        } else {
+            "file"
+        }
+    }
+
+    fn read(&self) -> Result<Option<String>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(&self.path)?;
+        if !crypto::is_sealed(&raw) {
+            return Ok(Some(raw));
+        }
+
+        let key = self.key.as_ref().ok_or_else(|| {
+            format!(
+                "{} is encrypted; set {} or {}",
+                self.path.display(),
+                constants::CREDENTIALS_PASSPHRASE_ENV,
+                constants::CREDENTIALS_KEY_FILE_ENV
+            )
+        })?;
+        crypto::open(&raw, key).map(Some)
+    }
+
+    fn write(&self, contents: &str) -> Result<(), Box<dyn Error>> {
+        let contents = match &self.key {
+            Some(key) => crypto::seal(contents, key)?,
+            None => contents.to_string(),
+        };
+        fs::write(&self.path, contents)?;
+
+        #[cfg(unix)]
+        {
+            let mut perms = fs::metadata(&self.path)?.permissions();
+            perms.set_mode(constants::SECURE_FILE_MODE);
+            fs::set_permissions(&self.path, perms)?;
+        }
+
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+/// The system keyring: Keychain on macOS, Secret Service on Linux, Credential Manager on Windows
+pub struct KeyringStore {
+    entry: keyring::Entry,
+    /// `credentials.json` written outside the app, moved into the keyring whenever it shows up
+    import_from: Option<FileStore>,
+}
+
+impl KeyringStore {
+    /// An instance with its own config directory gets its own entry, keyed by that directory
+    pub fn new() -> Result<Self, keyring::Error> {
+        Self::for_account(constants::KEYRING_ACCOUNT)
+    }
+
+    /// Entry under `account` instead of the profile set's, keyed by directory like [`new`](Self::new)
+    pub fn for_account(account: &str) -> Result<Self, keyring::Error> {
+        let dirs = paths::dirs();
+        let account = if dirs.isolated {
+            format!("{account}:{}", dirs.config.display())
+        } else {
+            account.to_string()
+        };
+        let entry = keyring::Entry::new(constants::KEYRING_SERVICE, &account)?;
+        Ok(Self { entry, import_from: None })
+    }
+
+    /// Import `file` whenever it is written, so edits to `credentials.json` still apply
+    #[must_use]
+    pub fn importing(self, file: FileStore) -> Self {
+        Self {
+            import_from: Some(file),
+            ..self
+        }
+    }
+
+    /// Whether the platform keyring can actually be reached, e.g. a Secret Service daemon is running
+    pub fn is_available(&self) -> bool {
+        matches!(self.entry.get_password(), Ok(_) | Err(keyring::Error::NoEntry))
+    }
+}
+
+impl CredentialStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn read(&self) -> Result<Option<String>, Box<dyn Error>> {
+        match self.entry.get_password() {
+            Ok(contents) => Ok(Some(contents)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, contents: &str) -> Result<(), Box<dyn Error>> {
+        self.entry.set_password(contents)?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        match self.entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn watched_path(&self) -> Option<&Path> {
+        self.import_from.as_ref().and_then(CredentialStore::path)
+    }
+
+    fn pending_import(&self) -> Result<Option<String>, Box<dyn Error>> {
+        match &self.import_from {
+            Some(file) => file.read(),
+            None => Ok(None),
+        }
+    }
+
+    fn import_watched(&self) -> Result<bool, Box<dyn Error>> {
+        match &self.import_from {
+            Some(file) => import(file, self),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Move stored credentials from `from` into `to`, unless `to` already has some.
+///
+/// Returns whether anything moved, see [`import`].
+pub fn migrate(from: &dyn CredentialStore, to: &dyn CredentialStore) -> Result<bool, Box<dyn Error>> {
+    if to.read()?.is_some() {
+        return Ok(false);
+    }
+    import(from, to)
+}
+
+/// Move stored credentials from `from` into `to`, replacing whatever `to` held.
+///
+/// The source is only cleared once the copy reads back intact. Returns whether anything moved.
+pub fn import(from: &dyn CredentialStore, to: &dyn CredentialStore) -> Result<bool, Box<dyn Error>> {
+    let Some(contents) = from.read()? else {
+        return Ok(false);
+    };
+
+    to.write(&contents)?;
+    if to.read()?.as_deref() != Some(contents.as_str()) {
+        return Err(format!("credentials copied to the {} store did not read back", to.name()).into());
+    }
+    from.clear()?;
+    Ok(true)
+}
+
+/// File store at `path`, encrypted when a passphrase or key file is configured
+fn file_store(path: PathBuf) -> FileStore {
+    match FileKey::from_env() {
+        Some(key) => {
+            let file = FileStore::encrypted(path, key);
+            let _ = file.encrypt_existing();
+            file
+        }
+        None => FileStore::new(path),
+    }
+}
+
+/// Keyring entry under `account`, unless the file store is asked for or no keyring is reachable
+fn keyring_store(account: &str) -> Option<KeyringStore> {
+    let wants_file = std::env::var(constants::CREDENTIAL_STORE_ENV).is_ok_and(|v| v.trim().eq_ignore_ascii_case("file"));
+    if wants_file {
+        return None;
+    }
+    KeyringStore::for_account(account).ok().filter(KeyringStore::is_available)
+}
+
+/// The system keyring, unless `SEEKERS_CREDENTIAL_STORE=file` is set or no keyring is reachable.
+///
+/// A plaintext `credentials.json` left by earlier versions is moved into the keyring on first use,
+/// and so is one written later, e.g. by a dotfile manager. The file store is encrypted when a passphrase or key file is configured, see [`FileKey::from_env`].
+pub fn default_store() -> Box<dyn CredentialStore> {
+    let file = file_store(FileStore::default_path());
+    match keyring_store(constants::KEYRING_ACCOUNT) {
+        Some(keyring) if migrate(&file, &keyring).is_ok() => Box::new(keyring.importing(file)),
+        _ => Box::new(file),
+    }
+}
+
+/// Where notification channel URLs and tokens are kept: chosen like [`default_store`], under
+/// its own keyring entry or in `channel-secrets.json`
+pub fn channel_store() -> Box<dyn CredentialStore> {
+    let file = file_store(paths::config_file(constants::CHANNEL_SECRETS_FILE));
+    match keyring_store(constants::KEYRING_CHANNELS_ACCOUNT) {
+        Some(keyring) if migrate(&file, &keyring).is_ok() => Box::new(keyring),
+        _ => Box::new(file),
+    }
+}
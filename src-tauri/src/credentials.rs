@@ -1,58 +1,249 @@
 use crate::constants;
+use crate::credential_store::{self, CredentialStore};
 use crate::Credentials;
-use std::fs;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-#[cfg(unix)]
-use std::os::unix::fs::PermissionsExt;
+/// A named account, polled independently of the others
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    pub org_id: String,
+    pub session_key: String,
+    /// Set when claude.ai rejects the session key; polling stops until new credentials are saved
+    #[serde(default)]
+    pub expired: bool,
+}
+
+impl Profile {
+    pub fn credentials(&self) -> Credentials {
+        Credentials {
+            org_id: self.org_id.clone(),
+            session_key: self.session_key.clone(),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.org_id.is_empty() && !self.session_key.is_empty()
+    }
+
+    /// Configured and not known to be expired
+    pub fn should_poll(&self) -> bool {
+        self.is_configured() && !self.expired
+    }
+}
 
-fn get_config_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let config_dir = PathBuf::from(home)
-        .join(".config")
-        .join(constants::CONFIG_DIR_NAME);
-    fs::create_dir_all(&config_dir).ok();
-    config_dir.join(constants::CREDENTIALS_FILE)
+/// Everything the credential store holds
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSet {
+    pub active: Option<String>,
+    pub profiles: Vec<Profile>,
 }
 
-pub struct CredentialsManager;
+/// Accepts both the current format and the single-account file written by earlier versions
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredCredentials {
+    Profiles(ProfileSet),
+    Legacy(Credentials),
+}
 
-impl CredentialsManager {
-    pub fn new() -> Self {
-        Self
+impl ProfileSet {
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
     }
 
-    pub fn save(&self, org_id: &str, session_key: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let path = get_config_path();
-        let creds = Credentials {
-            org_id: org_id.to_string(),
-            session_key: session_key.to_string(),
-        };
+    /// The selected profile, falling back to the first one if the selection is stale
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.active
+            .as_deref()
+            .and_then(|name| self.get(name))
+            .or_else(|| self.profiles.first())
+    }
 
-        let json = serde_json::to_string_pretty(&creds)?;
-        fs::write(&path, &json)?;
+    pub fn active_name(&self) -> Option<&str> {
+        self.active_profile().map(|p| p.name.as_str())
+    }
 
-        #[cfg(unix)]
-        {
-            let mut perms = fs::metadata(&path)?.permissions();
-            perms.set_mode(constants::SECURE_FILE_MODE);
-            fs::set_permissions(&path, perms)?;
+    /// Insert or replace a profile. When `previous_name` is given the profile is renamed, which
+    /// fails if another profile already has the new name.
+    ///
+    /// The expired flag survives unless the org ID or session key changed.
+    pub fn upsert(&mut self, mut profile: Profile, previous_name: Option<&str>) -> Result<(), String> {
+        let key = previous_name.unwrap_or(&profile.name).to_string();
+        if profile.name != key && self.get(&profile.name).is_some() {
+            return Err(format!("A profile named \"{}\" already exists", profile.name));
+        }
+        if let Some(existing) = self.get(&key) {
+            if existing.org_id == profile.org_id && existing.session_key == profile.session_key {
+                profile.expired |= existing.expired;
+            }
+        }
+
+        if self.active.as_deref() == Some(key.as_str()) {
+            self.active = Some(profile.name.clone());
+        }
+
+        match self.profiles.iter_mut().find(|p| p.name == key) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
         }
 
+        if self.active.is_none() {
+            self.active = self.profiles.first().map(|p| p.name.clone());
+        }
         Ok(())
     }
 
+    /// Flag a profile's session key as rejected. Returns `false` if it already was.
+    pub fn mark_expired(&mut self, name: &str) -> bool {
+        match self.profiles.iter_mut().find(|p| p.name == name) {
+            Some(profile) if !profile.expired => {
+                profile.expired = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.profiles.retain(|p| p.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = self.profiles.first().map(|p| p.name.clone());
+        }
+    }
+}
+
+pub struct CredentialsManager {
+    store: Box<dyn CredentialStore>,
+    /// The profile set as last read or written, so renders don't hit the keyring or run the
+    /// passphrase KDF each time
+    cache: Mutex<Option<ProfileSet>>,
+}
+
+impl Default for CredentialsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialsManager {
+    /// Uses the system keyring when available, see [`credential_store::default_store`]
+    pub fn new() -> Self {
+        Self::with_store(credential_store::default_store())
+    }
+
+    pub fn with_store(store: Box<dyn CredentialStore>) -> Self {
+        Self {
+            store,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Name of the backend in use, "keyring" or "file"
+    pub fn store_name(&self) -> &'static str {
+        self.store.name()
+    }
+
+    /// The file to watch for edits made outside the app: the credentials file, or with the
+    /// keyring a `credentials.json` that [`reload_profiles`](Self::reload_profiles) imports
+    pub fn watched_path(&self) -> Option<PathBuf> {
+        self.store.watched_path().map(Path::to_path_buf)
+    }
+
+    /// Update the active profile's credentials, creating a default profile if there is none.
+    ///
+    /// Saving always clears the expired flag so the profile is polled again.
+    pub fn save(&self, org_id: &str, session_key: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut set = self.load_profiles()?;
+        let name = set
+            .active_name()
+            .unwrap_or(constants::DEFAULT_PROFILE_NAME)
+            .to_string();
+
+        match set.profiles.iter_mut().find(|p| p.name == name) {
+            Some(profile) => {
+                profile.org_id = org_id.to_string();
+                profile.session_key = session_key.to_string();
+                profile.expired = false;
+            }
+            None => set.upsert(
+                Profile {
+                    name,
+                    org_id: org_id.to_string(),
+                    session_key: session_key.to_string(),
+                    expired: false,
+                },
+                None,
+            )?,
+        }
+        self.save_profiles(&set)
+    }
+
+    /// Credentials of the active profile
     pub fn load(&self) -> Result<Credentials, Box<dyn std::error::Error>> {
-        let path = get_config_path();
-        if !path.exists() {
-            return Ok(Credentials {
+        let set = self.load_profiles()?;
+        Ok(set.active_profile().map_or_else(
+            || Credentials {
                 org_id: String::new(),
                 session_key: String::new(),
-            });
+            },
+            Profile::credentials,
+        ))
+    }
+
+    pub fn save_profiles(&self, set: &ProfileSet) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(set)?;
+        self.store.write(&json)?;
+        *self.cache.lock().unwrap() = Some(set.clone());
+        Ok(())
+    }
+
+    /// The profile set, read from the store only the first time
+    pub fn load_profiles(&self) -> Result<ProfileSet, Box<dyn std::error::Error>> {
+        if let Some(set) = self.cache.lock().unwrap().as_ref() {
+            return Ok(set.clone());
         }
+        self.read_profiles()
+    }
 
-        let json = fs::read_to_string(&path)?;
-        let creds: Credentials = serde_json::from_str(&json)?;
-        Ok(creds)
+    /// Read the profile set from the store again, for edits made outside the app. A watched file
+    /// that isn't the store itself is imported first; one that doesn't parse is an error and
+    /// stays where it is.
+    pub fn reload_profiles(&self) -> Result<ProfileSet, Box<dyn std::error::Error>> {
+        if let Some(json) = self.store.pending_import()? {
+            parse_profiles(&json)?;
+            self.store.import_watched()?;
+        }
+        self.read_profiles()
     }
+
+    fn read_profiles(&self) -> Result<ProfileSet, Box<dyn std::error::Error>> {
+        let set = match self.store.read()? {
+            Some(json) => parse_profiles(&json)?,
+            None => ProfileSet::default(),
+        };
+        *self.cache.lock().unwrap() = Some(set.clone());
+        Ok(set)
+    }
+}
+
+/// The stored profile set, in either format of [`StoredCredentials`]
+fn parse_profiles(json: &str) -> Result<ProfileSet, serde_json::Error> {
+    let set = match serde_json::from_str(json)? {
+        StoredCredentials::Profiles(set) => set,
+        StoredCredentials::Legacy(creds) => ProfileSet {
+            active: Some(constants::DEFAULT_PROFILE_NAME.to_string()),
+            profiles: vec![Profile {
+                name: constants::DEFAULT_PROFILE_NAME.to_string(),
+                org_id: creds.org_id,
+                session_key: creds.session_key,
+                expired: false,
+            }],
+        },
+    };
+    Ok(set)
 }
@@ -0,0 +1,160 @@
+use crate::constants;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// Where the credentials file key comes from
+#[derive(Debug, Clone)]
+pub enum FileKey {
+    /// Stretched with Argon2id and a fresh salt on every write
+    Passphrase(String),
+    /// 32 random bytes, base64-encoded; created on first use
+    KeyFile(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Kdf {
+    Argon2id,
+    KeyFile,
+}
+
+/// On-disk format of an encrypted `credentials.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct Sealed {
+    version: u32,
+    kdf: Kdf,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl FileKey {
+    /// From `SEEKERS_CREDENTIALS_PASSPHRASE` or `SEEKERS_CREDENTIALS_KEY_FILE`, passphrase first
+    pub fn from_env() -> Option<Self> {
+        let non_empty = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        non_empty(constants::CREDENTIALS_PASSPHRASE_ENV)
+            .map(Self::Passphrase)
+            .or_else(|| non_empty(constants::CREDENTIALS_KEY_FILE_ENV).map(|path| Self::KeyFile(path.into())))
+    }
+
+    fn kdf(&self) -> Kdf {
+        match self {
+            Self::Passphrase(_) => Kdf::Argon2id,
+            Self::KeyFile(_) => Kdf::KeyFile,
+        }
+    }
+
+    /// The AES key; a missing key file is only generated when `create` is set
+    fn derive(&self, salt: &[u8], create: bool) -> Result<[u8; KEY_LEN], Box<dyn Error>> {
+        let mut key = [0u8; KEY_LEN];
+        match self {
+            Self::Passphrase(passphrase) => {
+                argon2::Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| format!("key derivation failed: {e}"))?;
+            }
+            Self::KeyFile(path) if create || path.exists() => key = load_or_create_key_file(path)?,
+            Self::KeyFile(path) => return Err(format!("key file {} does not exist", path.display()).into()),
+        }
+        Ok(key)
+    }
+}
+
+fn load_or_create_key_file(path: &Path) -> Result<[u8; KEY_LEN], Box<dyn Error>> {
+    if path.exists() {
+        let bytes = BASE64.decode(fs::read_to_string(path)?.trim())?;
+        return bytes
+            .try_into()
+            .map_err(|_| format!("{} is not a {KEY_LEN}-byte key", path.display()).into());
+    }
+
+    let mut key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, BASE64.encode(key))?;
+
+    #[cfg(unix)]
+    {
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(constants::SECURE_FILE_MODE);
+        fs::set_permissions(path, perms)?;
+    }
+
+    Ok(key)
+}
+
+/// Whether `contents` is an encrypted credentials file rather than plaintext JSON
+pub fn is_sealed(contents: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(contents).is_ok_and(|v| v.get("ciphertext").is_some())
+}
+
+/// Encrypt `plaintext` with AES-256-GCM into the versioned on-disk format
+pub fn seal(plaintext: &str, key: &FileKey) -> Result<String, Box<dyn Error>> {
+    let mut salt = [0u8; SALT_LEN];
+    if key.kdf() == Kdf::Argon2id {
+        OsRng.fill_bytes(&mut salt);
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.derive(&salt, true)?));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext.as_bytes(), aad: constants::CREDENTIALS_AAD })
+        .map_err(|_| "encryption failed")?;
+
+    let sealed = Sealed {
+        version: constants::ENCRYPTED_CREDENTIALS_VERSION,
+        kdf: key.kdf(),
+        salt: if key.kdf() == Kdf::Argon2id { BASE64.encode(salt) } else { String::new() },
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&sealed)?)
+}
+
+/// Decrypt a file written by [`seal`]. Fails on a wrong key or any tampering.
+pub fn open(contents: &str, key: &FileKey) -> Result<String, Box<dyn Error>> {
+    let version = serde_json::from_str::<serde_json::Value>(contents)?
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or_default();
+    if version > u64::from(constants::ENCRYPTED_CREDENTIALS_VERSION) {
+        return Err(format!("credentials were written by a newer Seekers (format {version})").into());
+    }
+
+    let sealed: Sealed = serde_json::from_str(contents)?;
+    if sealed.kdf != key.kdf() {
+        let expected = match sealed.kdf {
+            Kdf::Argon2id => constants::CREDENTIALS_PASSPHRASE_ENV,
+            Kdf::KeyFile => constants::CREDENTIALS_KEY_FILE_ENV,
+        };
+        return Err(format!("credentials are encrypted with a different key type; set {expected}").into());
+    }
+
+    let salt = BASE64.decode(&sealed.salt)?;
+    let nonce = BASE64.decode(&sealed.nonce)?;
+    if nonce.len() != 12 {
+        return Err("credentials file has an invalid nonce".into());
+    }
+    let ciphertext = BASE64.decode(&sealed.ciphertext)?;
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.derive(&salt, false)?));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: constants::CREDENTIALS_AAD })
+        .map_err(|_| "could not decrypt credentials: wrong passphrase or key file, or the file was modified")?;
+    Ok(String::from_utf8(plaintext)?)
+}
@@ -0,0 +1,198 @@
+use crate::constants;
+use crate::credentials::ProfileSet;
+use crate::error::FetchError;
+use crate::settings::{AppSettings, MenuBarDisplay, ProgressStyle, StaleMarker, TrayProfile};
+use crate::{UsageData, UsageWindow};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+pub fn format_tray_title(usage: &UsageData, settings: &AppSettings) -> String {
+    let five = usage.five_hour.as_ref().map(|w| w.utilization.round() as i32);
+    let seven = usage.seven_day.as_ref().map(|w| w.utilization.round() as i32);
+    
+    let value = match settings.menu_bar_display {
+        MenuBarDisplay::Session => five.map(|v| v.to_string()),
+        MenuBarDisplay::Weekly => seven.map(|v| v.to_string()),
+        MenuBarDisplay::Both => match (five, seven) {
+            (Some(f), Some(s)) => Some(format!("{f}/{s}")),
+            (Some(f), None) => Some(f.to_string()),
+            (None, Some(s)) => Some(s.to_string()),
+            _ => None,
+        },
+        MenuBarDisplay::Higher => match (five, seven) {
+            (Some(f), Some(s)) => Some(f.max(s).to_string()),
+            (Some(f), None) => Some(f.to_string()),
+            (None, Some(s)) => Some(s.to_string()),
+            _ => None,
+        },
+    };
+    
+    match value {
+        Some(v) if settings.show_percent_symbol => format!("{v}%"),
+        Some(v) => v,
+        None => "--".to_string(),
+    }
+}
+
+/// Menu bar title for the profile (or aggregate) selected in settings.
+///
+/// Prefixed with a warning marker while the shown profile's last fetch failed, and carrying
+/// the configured stale marker while the numbers are old or the last fetch failed, so they are
+/// never mistaken for current ones. An expired session key replaces the numbers entirely.
+pub fn tray_title<S: BuildHasher>(
+    set: &ProfileSet,
+    usage: &HashMap<String, UsageData, S>,
+    errors: &HashMap<String, FetchError, S>,
+    settings: &AppSettings,
+) -> String {
+    let aggregate = settings.tray_profile == TrayProfile::Aggregate;
+    let (shown, failing) = if aggregate {
+        let configured: Vec<_> = set.profiles.iter().filter(|p| p.is_configured()).collect();
+        if !configured.is_empty() && configured.iter().all(|p| p.expired) {
+            return constants::TRAY_TITLE_AUTH_NEEDED.to_string();
+        }
+        let failing = !errors.is_empty() || configured.iter().any(|p| p.expired);
+        (Some(aggregate_usage(usage.values())), failing)
+    } else {
+        let active = set.active_profile();
+        if active.is_some_and(|p| p.expired) {
+            return constants::TRAY_TITLE_AUTH_NEEDED.to_string();
+        }
+        (
+            active.and_then(|p| usage.get(&p.name)).cloned(),
+            active.is_some_and(|p| errors.contains_key(&p.name)),
+        )
+    };
+
+    let title = match shown {
+        Some(u) if failing || is_stale(&u, settings, Utc::now()) => {
+            let title = format_tray_title(&u, settings);
+            match settings.stale_marker {
+                StaleMarker::Question => format!("{title}{}", constants::TRAY_STALE_MARKER),
+                StaleMarker::Parentheses => format!("({title})"),
+                StaleMarker::Off => title,
+            }
+        }
+        Some(u) => format_tray_title(&u, settings),
+        None => constants::TRAY_TITLE_DEFAULT.to_string(),
+    };
+    if failing {
+        format!("{} {title}", constants::TRAY_ERROR_MARKER)
+    } else {
+        title
+    }
+}
+
+/// Age past which usage is stale: `stale_after_intervals` refresh intervals, with a floor
+pub fn stale_after(settings: &AppSettings) -> chrono::Duration {
+    let minutes = i64::from(settings.refresh_interval) * i64::from(settings.stale_after_intervals);
+    chrono::Duration::minutes(minutes.max(constants::stale::MIN_MINUTES))
+}
+
+/// Whether `usage` is older than [`stale_after`]; usage of unknown age counts as stale
+pub fn is_stale(usage: &UsageData, settings: &AppSettings, now: DateTime<Utc>) -> bool {
+    usage.fetched_at.is_none_or(|fetched_at| now - fetched_at > stale_after(settings))
+}
+
+pub fn make_progress_bar(pct: f64, settings: &AppSettings) -> String {
+    let len = settings.progress_length as usize;
+    let filled = ((pct / 100.0) * len as f64).round() as usize;
+    let empty = len - filled.min(len);
+    
+    let (filled_char, empty_char) = match settings.progress_style {
+        ProgressStyle::Circles => constants::progress::CIRCLES,
+        ProgressStyle::Blocks => constants::progress::BLOCKS,
+        ProgressStyle::Bar => constants::progress::BAR,
+        ProgressStyle::Dots => constants::progress::DOTS,
+    };
+    
+    format!("{}{}", filled_char.repeat(filled.min(len)), empty_char.repeat(empty))
+}
+
+pub fn format_duration(diff: chrono::Duration) -> String {
+    let days = diff.num_days();
+    let hours = diff.num_hours() % constants::time::HOURS_PER_DAY;
+    let mins = diff.num_minutes() % constants::time::MINUTES_PER_HOUR;
+
+    if days > 0 {
+        if hours > 0 {
+            format!("{days}d {hours}h")
+        } else {
+            format!("{days}d")
+        }
+    } else if hours > 0 {
+        if mins > 0 {
+            format!("{hours}h {mins}m")
+        } else {
+            format!("{hours}h")
+        }
+    } else {
+        format!("{}m", diff.num_minutes())
+    }
+}
+
+/// "Updated 3m ago" line for usage fetched at `fetched_at`
+pub fn format_updated(fetched_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+    match fetched_at.map(|fetched_at| now - fetched_at) {
+        Some(age) if age.num_minutes() < 1 => "Updated just now".to_string(),
+        Some(age) => format!("Updated {} ago", format_duration(age)),
+        None => "Last update unknown".to_string(),
+    }
+}
+
+pub fn format_time_until(date: DateTime<Utc>) -> String {
+    let diff = date.signed_duration_since(Utc::now());
+    if diff.num_seconds() <= 0 {
+        "any moment".to_string()
+    } else {
+        format!("in {}", format_duration(diff))
+    }
+}
+
+pub fn format_reset_time(iso_string: &str) -> String {
+    use chrono::Local;
+
+    if let Ok(date) = iso_string.parse::<DateTime<Utc>>() {
+        let diff = date.signed_duration_since(Utc::now());
+        let local = date.with_timezone(&Local);
+
+        if diff.num_hours() < constants::time::HOURS_PER_DAY {
+            format_time_until(date)
+        } else if diff.num_hours() < constants::time::HOURS_TOMORROW_THRESHOLD {
+            format!("tomorrow {}", local.format("%-I:%M %p"))
+        } else {
+            local.format("%a %-I:%M %p").to_string()
+        }
+    } else {
+        "unknown".to_string()
+    }
+}
+
+/// Combine several profiles' usage, keeping the most-used value of each window and the
+/// oldest fetch time
+pub fn aggregate_usage<'a>(usages: impl IntoIterator<Item = &'a UsageData>) -> UsageData {
+    fn higher(a: Option<UsageWindow>, b: Option<&UsageWindow>) -> Option<UsageWindow> {
+        match (a, b) {
+            (Some(a), Some(b)) if b.utilization > a.utilization => Some(b.clone()),
+            (Some(a), _) => Some(a),
+            (None, b) => b.cloned(),
+        }
+    }
+
+    let usages: Vec<&UsageData> = usages.into_iter().collect();
+    let fetched_at = usages.iter().map(|u| u.fetched_at).min().flatten();
+
+    usages.into_iter().fold(
+        UsageData {
+            five_hour: None,
+            seven_day: None,
+            fetched_at,
+        },
+        |acc, usage| UsageData {
+            five_hour: higher(acc.five_hour, usage.five_hour.as_ref()),
+            seven_day: higher(acc.seven_day, usage.seven_day.as_ref()),
+            fetched_at: acc.fetched_at,
+        },
+    )
+}
@@ -0,0 +1,125 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Why a usage fetch failed, in terms the user can act on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    /// 401/403: the session key was rejected
+    SessionExpired,
+    /// 404: the organization ID does not exist or is not visible to this key
+    OrgNotFound,
+    /// 429, with the `Retry-After` delay in seconds when the server sent one
+    RateLimited { retry_after: Option<u64> },
+    /// claude.ai could not be reached at all
+    Offline,
+    /// Any other transport failure
+    Network(String),
+    /// The response no longer matches the shape Seekers expects
+    SchemaChanged(String),
+    /// 5xx from claude.ai
+    Server { status: u16 },
+    /// Any other unexpected status
+    Unexpected { status: u16 },
+}
+
+impl FetchError {
+    pub fn from_status(status: reqwest::StatusCode, retry_after: Option<u64>) -> Self {
+        match status.as_u16() {
+            401 | 403 => Self::SessionExpired,
+            404 => Self::OrgNotFound,
+            429 => Self::RateLimited { retry_after },
+            code if status.is_server_error() => Self::Server { status: code },
+            code => Self::Unexpected { status: code },
+        }
+    }
+
+    /// Stable identifier for the frontend
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SessionExpired => "sessionExpired",
+            Self::OrgNotFound => "orgNotFound",
+            Self::RateLimited { .. } => "rateLimited",
+            Self::Offline => "offline",
+            Self::Network(_) => "network",
+            Self::SchemaChanged(_) => "schemaChanged",
+            Self::Server { .. } => "server",
+            Self::Unexpected { .. } => "unexpected",
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            Self::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SessionExpired => write!(f, "Session key expired — update in Settings"),
+            Self::OrgNotFound => write!(f, "Organization not found — check the ID in Settings"),
+            Self::RateLimited { retry_after: Some(secs) } => {
+                write!(f, "Rate limited by claude.ai — retry in {}m", secs.div_ceil(60))
+            }
+            Self::RateLimited { retry_after: None } => write!(f, "Rate limited by claude.ai"),
+            Self::Offline => write!(f, "Offline — can't reach claude.ai"),
+            Self::Network(detail) => write!(f, "Network error: {detail}"),
+            Self::SchemaChanged(_) => write!(f, "Unexpected response from claude.ai — Seekers may need an update"),
+            Self::Server { status } => write!(f, "claude.ai server error ({status})"),
+            Self::Unexpected { status } => write!(f, "API request failed: {status}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_connect() || e.is_timeout() {
+            Self::Offline
+        } else if e.is_decode() {
+            Self::SchemaChanged(e.to_string())
+        } else {
+            Self::Network(e.to_string())
+        }
+    }
+}
+
+/// Payload of the `config-error` event; `message` is `None` once the file is valid again
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigErrorEvent {
+    pub file: String,
+    pub message: Option<String>,
+}
+
+/// Payload of the `usage-error` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageErrorEvent {
+    pub profile: String,
+    pub kind: &'static str,
+    pub message: String,
+    pub retry_after: Option<u64>,
+}
+
+impl UsageErrorEvent {
+    pub fn new(profile: &str, error: &FetchError) -> Self {
+        Self {
+            profile: profile.to_string(),
+            kind: error.kind(),
+            message: error.to_string(),
+            retry_after: error.retry_after(),
+        }
+    }
+}
+
+/// Payload of the `channel-error` event, sent when an alert couldn't be delivered to a channel
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelErrorEvent {
+    pub channel: String,
+    pub message: String,
+}
@@ -0,0 +1,86 @@
+use crate::constants;
+use crate::history::UsageSample;
+use crate::{UsageData, WindowKind};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Projection of a window's utilization at its current consumption rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Forecast {
+    /// Percentage points consumed per hour
+    pub rate_per_hour: f64,
+    /// When utilization reaches 100% at this pace, if that happens before the window resets
+    pub limit_at: Option<DateTime<Utc>>,
+}
+
+fn lookback(kind: WindowKind) -> Duration {
+    match kind {
+        WindowKind::FiveHour => Duration::minutes(constants::forecast::FIVE_HOUR_LOOKBACK_MINUTES),
+        WindowKind::SevenDay => Duration::minutes(constants::forecast::SEVEN_DAY_LOOKBACK_MINUTES),
+    }
+}
+
+/// Whether two `resets_at` values describe the same window cycle.
+///
+/// The API jitters the sub-second part between calls, so exact string comparison is too strict.
+pub fn same_reset(a: &str, b: &str) -> bool {
+    match (a.parse::<DateTime<Utc>>(), b.parse::<DateTime<Utc>>()) {
+        (Ok(a), Ok(b)) => (a - b).num_seconds().abs() < constants::forecast::RESET_TOLERANCE_SECS,
+        _ => a == b,
+    }
+}
+
+/// Project a window forward from `samples` (oldest first, the last one being the current value).
+///
+/// Only samples from the current reset cycle within the lookback period are used, so a reset
+/// never shows up as a negative rate. Returns `None` until there is enough data to measure.
+pub fn project(kind: WindowKind, samples: &[UsageSample]) -> Option<Forecast> {
+    let latest = samples.last()?;
+    let current = latest.window(kind)?;
+    let since = latest.timestamp - lookback(kind);
+
+    let baseline = samples.iter().find(|s| {
+        s.timestamp >= since && s.window(kind).is_some_and(|w| same_reset(&w.resets_at, &current.resets_at))
+    })?;
+    let baseline_window = baseline.window(kind)?;
+
+    let span = latest.timestamp - baseline.timestamp;
+    if span < Duration::minutes(constants::forecast::MIN_SPAN_MINUTES) {
+        return None;
+    }
+
+    let hours = span.num_seconds() as f64 / 3600.0;
+    let rate_per_hour = (current.utilization - baseline_window.utilization) / hours;
+
+    let limit_at = if rate_per_hour > 0.0 && current.utilization < 100.0 {
+        let hours_left = (100.0 - current.utilization) / rate_per_hour;
+        let limit_at = latest.timestamp + Duration::seconds((hours_left * 3600.0) as i64);
+        match current.resets_at.parse::<DateTime<Utc>>() {
+            Ok(resets_at) if limit_at >= resets_at => None,
+            _ => Some(limit_at),
+        }
+    } else {
+        None
+    };
+
+    Some(Forecast {
+        rate_per_hour,
+        limit_at,
+    })
+}
+
+/// Earliest sample timestamp any projection made at `now` can use
+pub fn history_since(now: DateTime<Utc>) -> DateTime<Utc> {
+    now - Duration::minutes(constants::forecast::SEVEN_DAY_LOOKBACK_MINUTES)
+}
+
+/// Fill in the forecast of every window present in `usage`
+pub fn apply(usage: &mut UsageData, samples: &[UsageSample]) {
+    if let Some(ref mut five_hour) = usage.five_hour {
+        five_hour.forecast = project(WindowKind::FiveHour, samples);
+    }
+    if let Some(ref mut seven_day) = usage.seven_day {
+        seven_day.forecast = project(WindowKind::SevenDay, samples);
+    }
+}
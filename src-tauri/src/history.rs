@@ -0,0 +1,263 @@
+use crate::constants::{self, history};
+use crate::paths;
+use crate::{UsageData, UsageWindow, WindowKind};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+fn get_history_path() -> PathBuf {
+    paths::data_file(constants::HISTORY_FILE)
+}
+
+/// One window as recorded in the history store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowSample {
+    pub utilization: f64,
+    pub resets_at: String,
+}
+
+impl From<&UsageWindow> for WindowSample {
+    fn from(window: &UsageWindow) -> Self {
+        Self {
+            utilization: window.utilization,
+            resets_at: window.resets_at.clone(),
+        }
+    }
+}
+
+fn default_profile() -> String {
+    constants::DEFAULT_PROFILE_NAME.to_string()
+}
+
+/// A single fetch result, one line in `history.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSample {
+    pub timestamp: DateTime<Utc>,
+    /// Samples written before profiles existed belong to the default profile
+    #[serde(default = "default_profile")]
+    pub profile: String,
+    pub five_hour: Option<WindowSample>,
+    pub seven_day: Option<WindowSample>,
+}
+
+impl UsageSample {
+    pub fn from_usage(profile: &str, usage: &UsageData, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            profile: profile.to_string(),
+            five_hour: usage.five_hour.as_ref().map(WindowSample::from),
+            seven_day: usage.seven_day.as_ref().map(WindowSample::from),
+        }
+    }
+
+    pub fn window(&self, kind: WindowKind) -> Option<&WindowSample> {
+        match kind {
+            WindowKind::FiveHour => self.five_hour.as_ref(),
+            WindowKind::SevenDay => self.seven_day.as_ref(),
+        }
+    }
+}
+
+/// A single window's value at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub utilization: f64,
+    pub resets_at: String,
+}
+
+pub struct HistoryStore {
+    path: PathBuf,
+    /// Time of the last sample that triggered a prune
+    pruned_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryStore {
+    pub fn new() -> Self {
+        Self::at(get_history_path())
+    }
+
+    /// A store kept in `path` instead of the data directory
+    pub fn at(path: PathBuf) -> Self {
+        Self {
+            path,
+            pruned_at: Mutex::new(None),
+        }
+    }
+
+    /// Append `sample`, dropping samples older than the retention period about once a day
+    pub fn append(&self, sample: &UsageSample) -> Result<(), Box<dyn std::error::Error>> {
+        let mut line = serde_json::to_string(sample)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+
+        let due = {
+            let mut pruned_at = self.pruned_at.lock().unwrap();
+            let due = pruned_at
+                .is_none_or(|at| sample.timestamp - at >= Duration::hours(history::PRUNE_INTERVAL_HOURS));
+            if due {
+                *pruned_at = Some(sample.timestamp);
+            }
+            due
+        };
+        if due {
+            self.prune(sample.timestamp - Duration::days(history::RETENTION_DAYS))?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the file without samples taken before `before` and without unparseable lines.
+    /// Returns how many lines were dropped.
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+
+        let reader = BufReader::new(fs::File::open(&self.path)?);
+        let mut kept = String::new();
+        let mut dropped = 0;
+        for line in reader.lines() {
+            let line = line?;
+            match serde_json::from_str::<UsageSample>(&line) {
+                Ok(sample) if sample.timestamp >= before => {
+                    kept.push_str(&line);
+                    kept.push('\n');
+                }
+                _ => dropped += 1,
+            }
+        }
+        if dropped == 0 {
+            return Ok(0);
+        }
+
+        self.replace(&kept)?;
+        Ok(dropped)
+    }
+
+    /// Move the samples of profile `from` to its new name `to`. Returns how many were moved.
+    pub fn rename_profile(&self, from: &str, to: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+
+        let reader = BufReader::new(fs::File::open(&self.path)?);
+        let mut lines = String::new();
+        let mut moved = 0;
+        for line in reader.lines() {
+            let mut line = line?;
+            if let Ok(mut sample) = serde_json::from_str::<UsageSample>(&line) {
+                if sample.profile == from {
+                    sample.profile = to.to_string();
+                    line = serde_json::to_string(&sample)?;
+                    moved += 1;
+                }
+            }
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        if moved > 0 {
+            self.replace(&lines)?;
+        }
+        Ok(moved)
+    }
+
+    /// Drop the samples of a deleted profile. Returns how many were dropped.
+    pub fn remove_profile(&self, name: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+
+        let reader = BufReader::new(fs::File::open(&self.path)?);
+        let mut kept = String::new();
+        let mut dropped = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if serde_json::from_str::<UsageSample>(&line).is_ok_and(|sample| sample.profile == name) {
+                dropped += 1;
+                continue;
+            }
+            kept.push_str(&line);
+            kept.push('\n');
+        }
+        if dropped > 0 {
+            self.replace(&kept)?;
+        }
+        Ok(dropped)
+    }
+
+    /// Write `lines` aside and rename them over the file, so a crash never leaves a truncated history
+    fn replace(&self, lines: &str) -> std::io::Result<()> {
+        let temp = self.path.with_extension(history::REWRITE_TEMP_EXTENSION);
+        fs::write(&temp, lines)?;
+        fs::rename(&temp, &self.path)
+    }
+
+    /// Samples with `from <= timestamp <= to`, oldest first. Unparseable lines are skipped.
+    pub fn query(
+        &self,
+        profile: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageSample>, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let reader = BufReader::new(fs::File::open(&self.path)?);
+        let mut samples = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let Ok(sample) = serde_json::from_str::<UsageSample>(&line) else {
+                continue;
+            };
+            if profile.is_some_and(|profile| sample.profile != profile) {
+                continue;
+            }
+            if from.is_some_and(|from| sample.timestamp < from) {
+                continue;
+            }
+            if to.is_some_and(|to| sample.timestamp > to) {
+                continue;
+            }
+            samples.push(sample);
+        }
+
+        samples.sort_by_key(|s| s.timestamp);
+        Ok(samples)
+    }
+
+    pub fn query_window(
+        &self,
+        profile: Option<&str>,
+        kind: WindowKind,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<HistoryPoint>, Box<dyn std::error::Error>> {
+        let points = self
+            .query(profile, from, to)?
+            .into_iter()
+            .filter_map(|sample| {
+                sample.window(kind).map(|w| HistoryPoint {
+                    timestamp: sample.timestamp,
+                    utilization: w.utilization,
+                    resets_at: w.resets_at.clone(),
+                })
+            })
+            .collect();
+        Ok(points)
+    }
+}
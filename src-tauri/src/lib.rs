@@ -1,25 +1,69 @@
-mod claude;
+pub mod actions;
+pub mod api;
+pub mod channels;
+pub mod claude;
 mod constants;
-mod credentials;
-mod settings;
+pub mod credential_store;
+pub mod credentials;
+pub mod crypto;
+pub mod display;
+pub mod error;
+pub mod forecast;
+pub mod history;
+pub mod metrics;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod notify;
+pub mod paths;
+pub mod refresh;
+pub mod resume;
+pub mod scheduler;
+pub mod settings;
+pub mod watch;
 
-use credentials::CredentialsManager;
-use settings::{AppSettings, SettingsManager};
+use api::{ApiServer, ApiSource};
+use channels::ChannelConfig;
+use chrono::{DateTime, Utc};
+use credentials::{CredentialsManager, Profile, ProfileSet};
+use display::{format_reset_time, format_time_until, format_updated, is_stale, make_progress_bar, tray_title};
+use error::{ConfigErrorEvent, FetchError, UsageErrorEvent};
+use forecast::Forecast;
+use history::{HistoryPoint, HistoryStore, UsageSample};
+use metrics::Metrics;
+use notify::{Alert, AlertSource, NotifyState};
+use refresh::Pipeline;
+use resume::{ClockWatch, NetworkWatch};
+use scheduler::{until_next_reset, Scheduler};
+use settings::{AppSettings, SettingsManager, TrayProfile};
+use watch::FileWatch;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, MenuBuilder, MenuItem, MenuItemBuilder, SubmenuBuilder},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, State,
 };
 use tauri_plugin_notification::NotificationExt;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex, Notify};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UsageData {
     pub five_hour: Option<UsageWindow>,
     pub seven_day: Option<UsageWindow>,
+    /// When claude.ai returned these numbers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+impl UsageData {
+    pub fn window(&self, kind: WindowKind) -> Option<&UsageWindow> {
+        match kind {
+            WindowKind::FiveHour => self.five_hour.as_ref(),
+            WindowKind::SevenDay => self.seven_day.as_ref(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +71,15 @@ pub struct UsageData {
 pub struct UsageWindow {
     pub utilization: f64,
     pub resets_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forecast: Option<Forecast>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowKind {
+    FiveHour,
+    SevenDay,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,11 +92,69 @@ pub struct Credentials {
 pub struct AppState {
     credentials_manager: CredentialsManager,
     settings_manager: SettingsManager,
+    history_store: HistoryStore,
     http_client: reqwest::Client,
-    usage: Mutex<Option<UsageData>>,
+    /// Latest usage per profile name
+    usage: Mutex<HashMap<String, UsageData>>,
+    /// Last fetch failure per profile, cleared by the next successful fetch
+    errors: Mutex<HashMap<String, FetchError>>,
     settings: Mutex<AppSettings>,
-    last_notified_session: Mutex<Option<u32>>,
-    last_notified_weekly: Mutex<Option<u32>>,
+    notify_state: Mutex<NotifyState>,
+    /// Wakes the auto-refresh loop for an immediate refresh and a fresh schedule
+    refresh_now: Notify,
+    /// The active profile's usage after each fetch, for the local API's event stream
+    usage_events: broadcast::Sender<UsageData>,
+    /// Running while the local API is enabled
+    api_server: Mutex<Option<ApiServer>>,
+    /// Why the local API isn't running although it is enabled
+    api_error: Mutex<Option<String>>,
+    /// Fetch counters for `GET /metrics`
+    metrics: Metrics,
+}
+
+impl AppState {
+    fn pipeline(&self) -> Pipeline<'_> {
+        Pipeline {
+            client: &self.http_client,
+            settings: &self.settings,
+            history: &self.history_store,
+            metrics: &self.metrics,
+            usage: &self.usage,
+            errors: &self.errors,
+            notify_state: &self.notify_state,
+        }
+    }
+}
+
+impl ApiSource for AppState {
+    async fn usage(&self) -> HashMap<String, UsageData> {
+        self.usage.lock().await.clone()
+    }
+
+    fn history(
+        &self,
+        profile: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageSample>, String> {
+        self.history_store.query(profile, from, to).map_err(|e| e.to_string())
+    }
+
+    fn refresh(&self) {
+        self.refresh_now.notify_one();
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<UsageData> {
+        self.usage_events.subscribe()
+    }
+
+    async fn metrics(&self) -> Option<String> {
+        if !self.settings.lock().await.api.metrics {
+            return None;
+        }
+        let usage = self.usage.lock().await;
+        Some(self.metrics.render(&usage, Utc::now()))
+    }
 }
 
 #[tauri::command]
@@ -53,6 +164,7 @@ async fn get_credentials(state: State<'_, Arc<AppState>>) -> Result<Credentials,
 
 #[tauri::command]
 async fn save_credentials(
+    app: AppHandle,
     state: State<'_, Arc<AppState>>,
     org_id: String,
     session_key: String,
@@ -60,6 +172,104 @@ async fn save_credentials(
     state
         .credentials_manager
         .save(&org_id, &session_key)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(name) = state.credentials_manager.load_profiles().ok().and_then(|set| set.active_name().map(str::to_string)) {
+        state.errors.lock().await.remove(&name);
+    }
+    render(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_profiles(state: State<'_, Arc<AppState>>) -> Result<ProfileSet, String> {
+    state.credentials_manager.load_profiles().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_profile(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    profile: Profile,
+    previous_name: Option<String>,
+) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+
+    let mut set = state.credentials_manager.load_profiles().map_err(|e| e.to_string())?;
+    let key = previous_name.as_deref().unwrap_or(&profile.name);
+    let credentials_changed = set
+        .get(key)
+        .is_none_or(|existing| existing.org_id != profile.org_id || existing.session_key != profile.session_key);
+    let renamed = previous_name.clone().filter(|previous| *previous != profile.name);
+    let name = profile.name.clone();
+    set.upsert(profile, previous_name.as_deref())?;
+    state.credentials_manager.save_profiles(&set).map_err(|e| e.to_string())?;
+
+    if let Some(previous) = renamed {
+        rename_profile_state(&state, &previous, &name).await;
+    }
+    if credentials_changed {
+        state.errors.lock().await.remove(&name);
+    }
+
+    render(&app, &state).await;
+    Ok(())
+}
+
+/// Move everything kept under a profile's name to its new name
+async fn rename_profile_state(state: &AppState, from: &str, to: &str) {
+    let mut usage = state.usage.lock().await;
+    if let Some(data) = usage.remove(from) {
+        usage.insert(to.to_string(), data);
+    }
+    drop(usage);
+    let mut errors = state.errors.lock().await;
+    if let Some(error) = errors.remove(from) {
+        errors.insert(to.to_string(), error);
+    }
+    drop(errors);
+
+    let mut notify_state = state.notify_state.lock().await;
+    notify_state.rename_profile(from, to);
+    let _ = notify_state.save();
+    drop(notify_state);
+
+    let _ = state.history_store.rename_profile(from, to);
+}
+
+#[tauri::command]
+async fn delete_profile(app: AppHandle, state: State<'_, Arc<AppState>>, name: String) -> Result<(), String> {
+    let mut set = state.credentials_manager.load_profiles().map_err(|e| e.to_string())?;
+    set.remove(&name);
+    state.credentials_manager.save_profiles(&set).map_err(|e| e.to_string())?;
+    state.usage.lock().await.remove(&name);
+    state.errors.lock().await.remove(&name);
+
+    let mut notify_state = state.notify_state.lock().await;
+    notify_state.remove_profile(&name);
+    let _ = notify_state.save();
+    drop(notify_state);
+    let _ = state.history_store.remove_profile(&name);
+
+    render(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_active_profile(app: AppHandle, state: State<'_, Arc<AppState>>, name: String) -> Result<(), String> {
+    activate_profile(&app, &state, &name).await
+}
+
+#[tauri::command]
+async fn discover_organizations(
+    state: State<'_, Arc<AppState>>,
+    session_key: String,
+) -> Result<Vec<claude::Organization>, String> {
+    let base_url = claude::api_base(state.settings.lock().await.api_base_url.as_deref());
+    claude::fetch_organizations(&base_url, session_key.trim(), &state.http_client)
+        .await
         .map_err(|e| e.to_string())
 }
 
@@ -77,18 +287,67 @@ async fn save_settings(
 ) -> Result<(), String> {
     state.settings_manager.save(&new_settings).map_err(|e| e.to_string())?;
     
-    {
+    let reschedule = {
         let mut settings = state.settings.lock().await;
+        let reschedule = settings.refresh_interval != new_settings.refresh_interval
+            || settings.api_base_url != new_settings.api_base_url;
         *settings = new_settings;
+        reschedule
+    };
+    if reschedule {
+        state.refresh_now.notify_one();
     }
     
-    let usage = state.usage.lock().await;
-    let settings = state.settings.lock().await;
-    if let Some(ref usage_data) = *usage {
-        update_tray(&app, usage_data, &settings);
-    }
+    let api = apply_api_settings(&state).await;
+    render(&app, &state).await;
     
-    Ok(())
+    api
+}
+
+/// Where the local API can be reached, for the Settings window
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiInfo {
+    /// Set while the server is listening
+    url: Option<String>,
+    token: String,
+    /// Why the server couldn't start, e.g. the port is taken
+    error: Option<String>,
+}
+
+#[tauri::command]
+async fn get_api_info(state: State<'_, Arc<AppState>>) -> Result<ApiInfo, String> {
+    let url = state.api_server.lock().await.as_ref().map(ApiServer::url);
+    let error = state.api_error.lock().await.clone();
+    let token = api::load_or_create_token().map_err(|e| e.to_string())?;
+    Ok(ApiInfo { url, token, error })
+}
+
+#[tauri::command]
+async fn get_usage_history(
+    state: State<'_, Arc<AppState>>,
+    profile: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<UsageSample>, String> {
+    state
+        .history_store
+        .query(profile.as_deref(), from, to)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_window_history(
+    state: State<'_, Arc<AppState>>,
+    profile: Option<String>,
+    window: WindowKind,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<HistoryPoint>, String> {
+    state
+        .history_store
+        .query_window(profile.as_deref(), window, from, to)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -109,170 +368,281 @@ async fn test_notification(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Send a sample alert through one channel, without retries, so the Settings window can report the result
+#[tauri::command]
+async fn test_channel(state: State<'_, Arc<AppState>>, channel: ChannelConfig) -> Result<(), String> {
+    let alert = Alert {
+        title: "Seekers".to_string(),
+        body: "This is a test notification!".to_string(),
+        source: None,
+    };
+    let channel = ChannelConfig { retries: 0, ..channel };
+    channels::deliver(&state.http_client, &channel, &alert, std::time::Duration::ZERO).await
+}
+
 #[tauri::command]
 async fn refresh_usage(app: AppHandle, state: State<'_, Arc<AppState>>) -> Result<(), String> {
-    let creds = state.credentials_manager.load().map_err(|e| e.to_string())?;
+    let set = state.credentials_manager.load_profiles().map_err(|e| e.to_string())?;
 
-    if creds.org_id.is_empty() || creds.session_key.is_empty() {
+    let Some(profile) = set.active_profile().filter(|p| p.is_configured()) else {
         return Err("Credentials not configured".to_string());
+    };
+    if profile.expired {
+        return Err(FetchError::SessionExpired.to_string());
     }
 
-    let usage = claude::fetch_usage(&creds.org_id, &creds.session_key, &state.http_client)
-        .await
-        .map_err(|e| e.to_string())?;
+    let result = refresh_profile(&app, &state, profile, set.profiles.len() > 1).await;
+    render(&app, &state).await;
 
-    {
-        let mut stored = state.usage.lock().await;
-        *stored = Some(usage.clone());
-    }
+    result.map_err(|e| e.to_string())
+}
 
-    let _ = app.emit("usage-updated", &usage);
+async fn refresh_profile(
+    app: &AppHandle,
+    state: &Arc<AppState>,
+    profile: &Profile,
+    label_notifications: bool,
+) -> Result<(), FetchError> {
+    let label = label_notifications.then_some(profile.name.as_str());
+    let refreshed = match state.pipeline().refresh(profile, label).await {
+        Ok(refreshed) => refreshed,
+        Err(e) => {
+            if e == FetchError::SessionExpired {
+                expire_profile(app, state, &profile.name, label);
+            }
+            return Err(e);
+        }
+    };
 
-    let settings = state.settings.lock().await;
-    update_tray(&app, &usage, &settings);
-    
-    check_and_notify(&app, &state, &usage, &settings).await;
+    let set = state.credentials_manager.load_profiles().unwrap_or_default();
+    if set.active_name() == Some(profile.name.as_str()) {
+        let _ = state.usage_events.send(refreshed.usage.clone());
+    }
+    for reset in refreshed.resets {
+        let _ = app.emit("window-reset", reset);
+    }
+    let channels = state.settings.lock().await.channels.clone();
+    send_alerts(app, state, &channels, refreshed.alerts);
 
     Ok(())
 }
 
-async fn check_and_notify(app: &AppHandle, state: &Arc<AppState>, usage: &UsageData, settings: &AppSettings) {
-    if settings.notify_session > 0 {
-        if let Some(ref five_hour) = usage.five_hour {
-            let pct = five_hour.utilization.round() as u32;
-            if pct >= settings.notify_session {
-                let mut last = state.last_notified_session.lock().await;
-                if *last != Some(settings.notify_session) {
-                    *last = Some(settings.notify_session);
-                    let _ = app.notification()
-                        .builder()
-                        .title("Claude Session Limit")
-                        .body(format!("Session usage at {pct}%"))
-                        .show();
+/// Persist that a profile's key was rejected, stopping its polling, and notify the first time
+fn expire_profile(app: &AppHandle, state: &Arc<AppState>, profile: &str, label: Option<&str>) {
+    let Ok(mut set) = state.credentials_manager.load_profiles() else {
+        return;
+    };
+    if !set.mark_expired(profile) || state.credentials_manager.save_profiles(&set).is_err() {
+        return;
+    }
+
+    show_alert(app, notify::session_expired(label));
+}
+
+fn show_alert(app: &AppHandle, alert: Alert) {
+    let slot = (actions::SUPPORTED && alert.source.is_some()).then(actions::reserve).flatten();
+    if let Some(slot) = slot {
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _slot = slot;
+            match actions::show(&alert) {
+                Ok(Some(action)) => handle_action(&app, &action),
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("Couldn't show an alert with buttons, showing it without: {e}");
+                    show_plain_alert(&app, alert);
                 }
-            } else {
-                let mut last = state.last_notified_session.lock().await;
-                *last = None;
             }
-        }
+        });
+        return;
     }
-    
-    if settings.notify_weekly > 0 {
-        if let Some(ref seven_day) = usage.seven_day {
-            let pct = seven_day.utilization.round() as u32;
-            if pct >= settings.notify_weekly {
-                let mut last = state.last_notified_weekly.lock().await;
-                if *last != Some(settings.notify_weekly) {
-                    *last = Some(settings.notify_weekly);
-                    let _ = app.notification()
-                        .builder()
-                        .title("Claude Weekly Limit")
-                        .body(format!("Weekly usage at {pct}%"))
-                        .show();
-                }
-            } else {
-                let mut last = state.last_notified_weekly.lock().await;
-                *last = None;
+
+    show_plain_alert(app, alert);
+}
+
+fn show_plain_alert(app: &AppHandle, alert: Alert) {
+    let shown = app.notification()
+        .builder()
+        .title(alert.title)
+        .body(alert.body)
+        .show();
+    if let Err(e) = shown {
+        eprintln!("Couldn't show an alert: {e}");
+    }
+}
+
+/// Show alerts as desktop notifications and push them to the configured channels in the background
+fn send_alerts(app: &AppHandle, state: &Arc<AppState>, channels: &[ChannelConfig], alerts: Vec<Alert>) {
+    if alerts.is_empty() {
+        return;
+    }
+
+    if channels.iter().any(|c| c.enabled) {
+        let app = app.clone();
+        let client = state.http_client.clone();
+        let channels = channels.to_vec();
+        let sent = alerts.clone();
+        tauri::async_runtime::spawn(async move {
+            for failure in channels::dispatch(&client, &channels, &sent).await {
+                let _ = app.emit("channel-error", failure);
             }
-        }
+        });
+    }
+
+    for alert in alerts {
+        show_alert(app, alert);
     }
 }
 
-fn update_tray(app: &AppHandle, usage: &UsageData, settings: &AppSettings) {
-    if let Some(tray) = app.tray_by_id(constants::TRAY_ID) {
-        let title = format_tray_title(usage, settings);
-        let _ = tray.set_title(Some(&title));
-        
-        if let Ok(menu) = create_tray_menu(app, Some(usage), settings) {
-            let _ = tray.set_menu(Some(menu));
-        }
+async fn activate_profile(app: &AppHandle, state: &Arc<AppState>, name: &str) -> Result<(), String> {
+    let mut set = state.credentials_manager.load_profiles().map_err(|e| e.to_string())?;
+    if set.get(name).is_none() {
+        return Err(format!("Unknown profile: {name}"));
     }
+    set.active = Some(name.to_string());
+    state.credentials_manager.save_profiles(&set).map_err(|e| e.to_string())?;
+
+    set_tray_profile(state, TrayProfile::Active).await?;
+    render(app, state).await;
+    Ok(())
 }
 
-fn format_tray_title(usage: &UsageData, settings: &AppSettings) -> String {
-    let five = usage.five_hour.as_ref().map(|w| w.utilization.round() as i32);
-    let seven = usage.seven_day.as_ref().map(|w| w.utilization.round() as i32);
-    
-    let value = match settings.menu_bar_display.as_str() {
-        "session" => five.map(|v| v.to_string()),
-        "weekly" => seven.map(|v| v.to_string()),
-        "both" => match (five, seven) {
-            (Some(f), Some(s)) => Some(format!("{f}/{s}")),
-            (Some(f), None) => Some(f.to_string()),
-            (None, Some(s)) => Some(s.to_string()),
-            _ => None,
-        },
-        "higher" => match (five, seven) {
-            (Some(f), Some(s)) => Some(f.max(s).to_string()),
-            (Some(f), None) => Some(f.to_string()),
-            (None, Some(s)) => Some(s.to_string()),
-            _ => None,
-        },
-        _ => five.map(|v| v.to_string()),
-    };
-    
-    match value {
-        Some(v) if settings.show_percent_symbol => format!("{v}%"),
-        Some(v) => v,
-        None => "--".to_string(),
+async fn set_tray_profile(state: &Arc<AppState>, mode: TrayProfile) -> Result<(), String> {
+    let mut settings = state.settings.lock().await;
+    if settings.tray_profile != mode {
+        settings.tray_profile = mode;
+        state.settings_manager.save(&settings).map_err(|e| e.to_string())?;
     }
+    Ok(())
 }
 
-fn make_progress_bar(pct: f64, settings: &AppSettings) -> String {
-    let len = settings.progress_length as usize;
-    let filled = ((pct / 100.0) * len as f64).round() as usize;
-    let empty = len - filled.min(len);
-    
-    let (filled_char, empty_char) = match settings.progress_style.as_str() {
-        "blocks" => constants::progress::BLOCKS,
-        "bar" => constants::progress::BAR,
-        "dots" => constants::progress::DOTS,
-        _ => constants::progress::CIRCLES,
+/// Re-render the tray from stored state and push the active profile's status to the frontend
+async fn render(app: &AppHandle, state: &Arc<AppState>) {
+    let set = state.credentials_manager.load_profiles().unwrap_or_default();
+    let usage = state.usage.lock().await;
+    let errors = state.errors.lock().await;
+    let settings = state.settings.lock().await;
+
+    update_tray(app, &set, &usage, &errors, &settings);
+
+    let Some(active) = set.active_profile() else {
+        return;
     };
-    
-    format!("{}{}", filled_char.repeat(filled.min(len)), empty_char.repeat(empty))
+    let error = if active.expired {
+        Some(FetchError::SessionExpired)
+    } else {
+        errors.get(&active.name).cloned()
+    };
+    if let Some(error) = error {
+        let _ = app.emit("usage-error", UsageErrorEvent::new(&active.name, &error));
+    } else if let Some(usage) = usage.get(&active.name) {
+        let _ = app.emit("usage-updated", usage);
+    }
 }
 
-fn create_tray_menu(app: &AppHandle, usage: Option<&UsageData>, settings: &AppSettings) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
-    let mut builder = MenuBuilder::new(app);
+fn update_tray(
+    app: &AppHandle,
+    set: &ProfileSet,
+    usage: &HashMap<String, UsageData>,
+    errors: &HashMap<String, FetchError>,
+    settings: &AppSettings,
+) {
+    if let Some(tray) = app.tray_by_id(constants::TRAY_ID) {
+        let title = tray_title(set, usage, errors, settings);
+        let _ = tray.set_title(Some(&title));
+        
+        if let Ok(menu) = create_tray_menu(app, set, usage, errors, settings) {
+            let _ = tray.set_menu(Some(menu));
+        }
+    }
+}
 
-    if let Some(usage) = usage {
-        if let Some(ref five_hour) = usage.five_hour {
-            let pct = five_hour.utilization.round() as i32;
-            let bar = make_progress_bar(five_hour.utilization, settings);
-            let item = MenuItemBuilder::new(format!(
-                "Session  {bar} {pct:>3}%"
-            ))
+fn window_menu_items(
+    app: &AppHandle,
+    label: &str,
+    window: &UsageWindow,
+    settings: &AppSettings,
+) -> tauri::Result<Vec<MenuItem<tauri::Wry>>> {
+    let pct = window.utilization.round() as i32;
+    let bar = make_progress_bar(window.utilization, settings);
+    let mut items = vec![
+        MenuItemBuilder::new(format!("{label:<8} {bar} {pct:>3}%"))
             .enabled(false)
-            .build(app)?;
-            builder = builder.item(&item);
-            
-            let reset = MenuItemBuilder::new(format!(
-                "         ↻ {}",
-                format_reset_time(&five_hour.resets_at)
-            ))
+            .build(app)?,
+        MenuItemBuilder::new(format!("         ↻ {}", format_reset_time(&window.resets_at)))
             .enabled(false)
-            .build(app)?;
-            builder = builder.item(&reset);
-        }
+            .build(app)?,
+    ];
 
-        if let Some(ref seven_day) = usage.seven_day {
-            let pct = seven_day.utilization.round() as i32;
-            let bar = make_progress_bar(seven_day.utilization, settings);
-            let item = MenuItemBuilder::new(format!(
-                "Weekly   {bar} {pct:>3}%"
-            ))
-            .enabled(false)
-            .build(app)?;
-            builder = builder.item(&item);
-            
-            let reset = MenuItemBuilder::new(format!(
-                "         ↻ {}",
-                format_reset_time(&seven_day.resets_at)
+    if let Some(limit_at) = window.forecast.as_ref().and_then(|f| f.limit_at) {
+        items.push(
+            MenuItemBuilder::new(format!(
+                "         ⚠ at this pace: limit {}",
+                format_time_until(limit_at)
             ))
             .enabled(false)
-            .build(app)?;
-            builder = builder.item(&reset);
+            .build(app)?,
+        );
+    }
+
+    Ok(items)
+}
+
+fn create_tray_menu(
+    app: &AppHandle,
+    set: &ProfileSet,
+    usage: &HashMap<String, UsageData>,
+    errors: &HashMap<String, FetchError>,
+    settings: &AppSettings,
+) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
+    let mut builder = MenuBuilder::new(app);
+    let multiple = set.profiles.len() > 1;
+
+    if set.profiles.iter().any(Profile::is_configured) {
+        for profile in &set.profiles {
+            if multiple {
+                let marker = if set.active_name() == Some(profile.name.as_str()) { "●" } else { "○" };
+                let header = MenuItemBuilder::new(format!("{marker} {}", profile.name))
+                    .enabled(false)
+                    .build(app)?;
+                builder = builder.item(&header);
+            }
+
+            let error = if profile.expired {
+                Some(FetchError::SessionExpired)
+            } else {
+                errors.get(&profile.name).cloned()
+            };
+            if let Some(error) = &error {
+                let item = MenuItemBuilder::new(format!("{} {error}", constants::TRAY_ERROR_MARKER))
+                    .enabled(false)
+                    .build(app)?;
+                builder = builder.item(&item);
+            }
+
+            let Some(usage) = usage.get(&profile.name) else {
+                let item = MenuItemBuilder::new("         No data yet")
+                    .enabled(false)
+                    .build(app)?;
+                builder = builder.item(&item);
+                continue;
+            };
+
+            let windows = [("Session", usage.five_hour.as_ref()), ("Weekly", usage.seven_day.as_ref())];
+            for (label, window) in windows {
+                if let Some(window) = window {
+                    for item in window_menu_items(app, label, window, settings)? {
+                        builder = builder.item(&item);
+                    }
+                }
+            }
+
+            let now = Utc::now();
+            let stale = if error.is_none() && is_stale(usage, settings, now) { " (stale)" } else { "" };
+            let updated = MenuItemBuilder::new(format!("         {}{stale}", format_updated(usage.fetched_at, now)))
+                .enabled(false)
+                .build(app)?;
+            builder = builder.item(&updated);
         }
 
         builder = builder.separator();
@@ -283,6 +653,24 @@ fn create_tray_menu(app: &AppHandle, usage: Option<&UsageData>, settings: &AppSe
         builder = builder.item(&item).separator();
     }
 
+    if multiple {
+        let aggregate = settings.tray_profile == TrayProfile::Aggregate;
+        let mut switcher = SubmenuBuilder::new(app, "Switch Profile");
+        for profile in &set.profiles {
+            let item = CheckMenuItemBuilder::with_id(
+                format!("{}{}", constants::menu::PROFILE_PREFIX, profile.name),
+                &profile.name,
+            )
+            .checked(!aggregate && set.active_name() == Some(profile.name.as_str()))
+            .build(app)?;
+            switcher = switcher.item(&item);
+        }
+        let highest = CheckMenuItemBuilder::with_id(constants::menu::PROFILE_AGGREGATE, "Highest of All Profiles")
+            .checked(aggregate)
+            .build(app)?;
+        builder = builder.item(&switcher.separator().item(&highest).build()?).separator();
+    }
+
     let open_claude = MenuItemBuilder::with_id(constants::menu::OPEN_CLAUDE, "Open Claude").build(app)?;
     let refresh = MenuItemBuilder::with_id(constants::menu::REFRESH, "Refresh").build(app)?;
     let settings_item = MenuItemBuilder::with_id(constants::menu::SETTINGS, "Settings...").build(app)?;
@@ -297,126 +685,374 @@ fn create_tray_menu(app: &AppHandle, usage: Option<&UsageData>, settings: &AppSe
         .build()
 }
 
-fn format_reset_time(iso_string: &str) -> String {
-    use chrono::{DateTime, Local, Utc};
+/// Refresh every pollable profile and re-render. Returns the errors of this round.
+async fn do_refresh(app: &AppHandle, state: &Arc<AppState>) -> Vec<FetchError> {
+    let Ok(set) = state.credentials_manager.load_profiles() else {
+        return Vec::new();
+    };
 
-    if let Ok(date) = iso_string.parse::<DateTime<Utc>>() {
-        let now = Utc::now();
-        let diff = date.signed_duration_since(now);
-        let local = date.with_timezone(&Local);
+    let mut errors = Vec::new();
+    for profile in set.profiles.iter().filter(|p| p.should_poll()) {
+        if let Err(e) = refresh_profile(app, state, profile, set.profiles.len() > 1).await {
+            errors.push(e);
+        }
+    }
 
-        if diff.num_seconds() <= 0 {
-            "any moment".to_string()
-        } else if diff.num_minutes() < constants::time::MINUTES_PER_HOUR {
-            format!("in {}m", diff.num_minutes())
-        } else if diff.num_hours() < constants::time::HOURS_PER_DAY {
-            let hours = diff.num_hours();
-            let mins = diff.num_minutes() % constants::time::MINUTES_PER_HOUR;
-            if mins > 0 {
-                format!("in {hours}h {mins}m")
-            } else {
-                format!("in {hours}h")
+    render(app, state).await;
+    errors
+}
+
+/// Refresh at the pace picked by the scheduler, just after each window resets, and whenever
+/// `refresh_now` is notified. Refreshes once right away, even with auto-refresh off, which
+/// otherwise leaves refreshing to `refresh_now`.
+fn start_auto_refresh(app: AppHandle, state: Arc<AppState>) {
+    tauri::async_runtime::spawn(async move {
+        let mut scheduler = Scheduler::new();
+        let mut delay = Some(tokio::time::Duration::ZERO);
+
+        loop {
+            // `delay` is only unset while auto-refresh is off
+            let until_reset = match delay {
+                Some(_) => until_next_reset(state.usage.lock().await.values(), Utc::now()),
+                None => None,
+            };
+            let wait = delay.into_iter().chain(until_reset).min();
+            let sleep = async {
+                match wait {
+                    Some(wait) => tokio::time::sleep(wait).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                () = sleep => {}
+                () = state.refresh_now.notified() => {}
             }
-        } else if diff.num_hours() < constants::time::HOURS_TOMORROW_THRESHOLD {
-            format!("tomorrow {}", local.format("%-I:%M %p"))
-        } else {
-            local.format("%a %-I:%M %p").to_string()
+            let errors = do_refresh(&app, &state).await;
+
+            // Read after waking, so a changed interval applies to the very next delay
+            let interval = state.settings.lock().await.refresh_interval;
+            delay = if interval == 0 {
+                None
+            } else {
+                let interval = tokio::time::Duration::from_secs(u64::from(interval) * constants::time::SECONDS_PER_MINUTE);
+                let usage = state.usage.lock().await;
+                Some(scheduler.next_delay(interval, &errors, usage.values(), Utc::now()).0)
+            };
         }
-    } else {
-        "unknown".to_string()
-    }
+    });
 }
 
-async fn do_refresh(app: &AppHandle, state: &Arc<AppState>) {
-    let Ok(creds) = state.credentials_manager.load() else {
-        return;
-    };
-    if creds.org_id.is_empty() || creds.session_key.is_empty() {
-        return;
-    }
-    if let Ok(usage) = claude::fetch_usage(&creds.org_id, &creds.session_key, &state.http_client).await {
-        let mut stored = state.usage.lock().await;
-        *stored = Some(usage.clone());
-        drop(stored);
-        
-        let settings = state.settings.lock().await;
-        update_tray(app, &usage, &settings);
-        check_and_notify(app, state, &usage, &settings).await;
-        drop(settings);
-        
-        let _ = app.emit("usage-updated", &usage);
-    }
+/// Refresh as soon as the machine wakes from sleep or claude.ai is reachable again, and
+/// re-render in between refreshes so staleness and "Updated 3m ago" stay current
+fn start_resume_watcher(app: AppHandle, state: Arc<AppState>) {
+    tauri::async_runtime::spawn(async move {
+        let mut clock = ClockWatch::new();
+        let mut network = NetworkWatch::new();
+        let mut stale = 0;
+        let mut rendered = std::time::Instant::now();
+
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(constants::resume::CHECK_INTERVAL_SECS)).await;
+
+            let offline = state
+                .errors
+                .lock()
+                .await
+                .values()
+                .any(|e| matches!(e, FetchError::Offline | FetchError::Network(_)));
+            let reconnected = if offline {
+                let base_url = claude::api_base(state.settings.lock().await.api_base_url.as_deref());
+                network.update(resume::reachable(&base_url).await)
+            } else {
+                network.update(true);
+                false
+            };
+
+            if clock.check().is_some() || reconnected {
+                state.refresh_now.notify_one();
+                continue;
+            }
+
+            let now_stale = {
+                let usage = state.usage.lock().await;
+                let settings = state.settings.lock().await;
+                usage.values().filter(|u| is_stale(u, &settings, Utc::now())).count()
+            };
+            if now_stale != stale || rendered.elapsed().as_secs() >= constants::resume::RERENDER_SECS {
+                stale = now_stale;
+                rendered = std::time::Instant::now();
+                render(&app, &state).await;
+            }
+        }
+    });
 }
 
-fn start_auto_refresh(app: AppHandle, state: Arc<AppState>) {
+/// Send the digest of alerts held back during quiet hours once they end, even without a refresh
+fn start_quiet_hours_watcher(app: AppHandle, state: Arc<AppState>) {
     tauri::async_runtime::spawn(async move {
         loop {
-            let interval = {
+            tokio::time::sleep(tokio::time::Duration::from_secs(constants::time::QUIET_HOURS_CHECK_SECS)).await;
+
+            let (schedule, channels) = {
                 let settings = state.settings.lock().await;
-                settings.refresh_interval
+                (settings.quiet_hours.clone(), settings.channels.clone())
             };
-            
-            if interval == 0 {
-                tokio::time::sleep(tokio::time::Duration::from_secs(constants::time::DISABLED_REFRESH_CHECK_SECS)).await;
-                continue;
+            let digest = {
+                let mut notify_state = state.notify_state.lock().await;
+                let digest = notify_state.release(&schedule, Utc::now());
+                if digest.is_some() {
+                    let _ = notify_state.save();
+                }
+                digest
+            };
+            send_alerts(&app, &state, &channels, digest.into_iter().collect());
+        }
+    });
+}
+
+/// Poll the settings and credentials files and apply edits made outside the app
+fn start_config_watcher(app: AppHandle, state: Arc<AppState>) {
+    tauri::async_runtime::spawn(async move {
+        let mut settings_watch = FileWatch::new(state.settings_manager.path());
+        let mut credentials_watch = state.credentials_manager.watched_path().map(FileWatch::new);
+        let mut profiles = state.credentials_manager.load_profiles().unwrap_or_default();
+        let mut reported = HashMap::new();
+
+        // Invalid fields in the file the app started with fell back to their defaults
+        if let Ok((_, rejected)) = state.settings_manager.load_over(&AppSettings::default()) {
+            report_config_result(&app, &mut reported, constants::SETTINGS_FILE, rejected_fields(&rejected));
+        }
+        // With the keyring, a `credentials.json` written while the app wasn't running is imported now
+        if credentials_watch.as_ref().is_some_and(|watch| watch.path().exists()) {
+            let result = reload_credentials(&app, &state, &mut profiles).await;
+            report_config_result(&app, &mut reported, constants::CREDENTIALS_FILE, result);
+        }
+
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(constants::time::CONFIG_WATCH_INTERVAL_SECS)).await;
+
+            if settings_watch.changed() {
+                let result = reload_settings(&app, &state).await;
+                report_config_result(&app, &mut reported, constants::SETTINGS_FILE, result);
+            }
+            if let Some(watch) = credentials_watch.as_mut() {
+                if watch.changed() {
+                    let result = reload_credentials(&app, &state, &mut profiles).await;
+                    report_config_result(&app, &mut reported, constants::CREDENTIALS_FILE, result);
+                }
             }
-            
-            tokio::time::sleep(tokio::time::Duration::from_secs(u64::from(interval) * constants::time::SECONDS_PER_MINUTE)).await;
-            do_refresh(&app, &state).await;
         }
     });
 }
 
+/// Apply `settings.json` if it differs from the settings in use, then refresh. Fields with
+/// invalid values keep their current value and are reported.
+async fn reload_settings(app: &AppHandle, state: &Arc<AppState>) -> Result<(), String> {
+    let current = state.settings.lock().await.clone();
+    let (loaded, rejected) = state.settings_manager.load_over(&current).map_err(|e| e.to_string())?;
+    let mut api = Ok(());
+    if current != loaded {
+        *state.settings.lock().await = loaded.clone();
+        api = apply_api_settings(state).await;
+        let _ = app.emit("settings-changed", &loaded);
+        state.refresh_now.notify_one();
+        render(app, state).await;
+    }
+    api.and(rejected_fields(&rejected))
+}
+
+/// Error listing settings fields that were rejected, if any
+fn rejected_fields(rejected: &[String]) -> Result<(), String> {
+    if rejected.is_empty() {
+        return Ok(());
+    }
+    let verb = if rejected.len() == 1 { "is" } else { "are" };
+    Err(format!("{} {verb} invalid; the previous value stays in use", rejected.join(", ")))
+}
+
+/// Start, stop or move the local API so it matches the settings. The error, e.g. a port in
+/// use, is also kept for [`get_api_info`].
+async fn apply_api_settings(state: &Arc<AppState>) -> Result<(), String> {
+    let settings = state.settings.lock().await.api.clone();
+    let mut server = state.api_server.lock().await;
+    if settings.enabled && server.as_ref().is_some_and(|s| s.port() == settings.port) {
+        return Ok(());
+    }
+
+    // Dropping the old server frees its port before the new one binds
+    *server = None;
+    let mut result = Ok(());
+    if settings.enabled {
+        result = match api::load_or_create_token() {
+            Ok(token) => match ApiServer::start(settings.port, token, state.clone()).await {
+                Ok(started) => {
+                    *server = Some(started);
+                    Ok(())
+                }
+                Err(e) => Err(format!("Couldn't listen on port {}: {e}", settings.port)),
+            },
+            Err(e) => Err(format!("Couldn't create the API token: {e}")),
+        };
+    }
+    *state.api_error.lock().await = result.as_ref().err().cloned();
+    result
+}
+
+/// Apply `credentials.json`, dropping state of removed profiles and refreshing changed ones
+async fn reload_credentials(app: &AppHandle, state: &Arc<AppState>, previous: &mut ProfileSet) -> Result<(), String> {
+    let set = state.credentials_manager.reload_profiles().map_err(|e| e.to_string())?;
+    if set == *previous {
+        return Ok(());
+    }
+
+    state.usage.lock().await.retain(|name, _| set.get(name).is_some());
+    state.errors.lock().await.retain(|name, _| set.get(name).is_some());
+
+    let changed = set
+        .profiles
+        .iter()
+        .filter(|p| p.should_poll() && previous.get(&p.name) != Some(*p));
+    for profile in changed {
+        let _ = refresh_profile(app, state, profile, set.profiles.len() > 1).await;
+    }
+    *previous = set;
+
+    let _ = app.emit("profiles-changed", ());
+    render(app, state).await;
+    Ok(())
+}
+
+/// Surface a config file that failed to apply, once per distinct error
+fn report_config_result(
+    app: &AppHandle,
+    reported: &mut HashMap<&'static str, String>,
+    file: &'static str,
+    result: Result<(), String>,
+) {
+    match result {
+        Ok(()) => {
+            if reported.remove(file).is_some() {
+                let _ = app.emit("config-error", ConfigErrorEvent { file: file.to_string(), message: None });
+            }
+        }
+        Err(message) => {
+            if reported.get(file) == Some(&message) {
+                return;
+            }
+            show_alert(app, notify::config_error(file, &message));
+            let _ = app.emit("config-error", ConfigErrorEvent { file: file.to_string(), message: Some(message.clone()) });
+            reported.insert(file, message);
+        }
+    }
+}
+
+/// Handle a tray menu entry or an alert's action button by its ID
+fn handle_action(app: &AppHandle, id: &str) {
+    if let Some(name) = id.strip_prefix(constants::menu::PROFILE_PREFIX) {
+        let app = app.clone();
+        let name = name.to_string();
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<Arc<AppState>>();
+            let _ = activate_profile(&app, &state, &name).await;
+        });
+        return;
+    }
+
+    if let Some(source) = actions::parse_snooze(id) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<Arc<AppState>>();
+            snooze(&state, &source).await;
+        });
+        return;
+    }
+
+    match id {
+        constants::menu::OPEN_CLAUDE => {
+            let _ = open::that(constants::CLAUDE_URL);
+        }
+        constants::menu::REFRESH => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<Arc<AppState>>();
+                do_refresh(&app, &state).await;
+            });
+        }
+        constants::menu::PROFILE_AGGREGATE => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<Arc<AppState>>();
+                if set_tray_profile(&state, TrayProfile::Aggregate).await.is_ok() {
+                    render(&app, &state).await;
+                }
+            });
+        }
+        constants::menu::SETTINGS => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        constants::menu::QUIT => {
+            app.exit(0);
+        }
+        _ => {}
+    }
+}
+
+/// Silence `source`'s window for an hour, from the Snooze action on its alert
+async fn snooze(state: &Arc<AppState>, source: &AlertSource) {
+    let until = Utc::now() + chrono::Duration::minutes(constants::notify::SNOOZE_MINUTES);
+    let mut notify_state = state.notify_state.lock().await;
+    notify_state.snooze(&source.profile, source.window, until);
+    let _ = notify_state.save();
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    paths::init(paths::config_dir_arg(std::env::args().skip(1)));
+
     tauri::Builder::default()
         .plugin(tauri_plugin_notification::init())
         .setup(|app| {
+            // Alerts with buttons bypass the notification plugin, so they need the app's identity too
+            #[cfg(target_os = "macos")]
+            let _ = mac_notification_sys::set_application(if tauri::is_dev() {
+                "com.apple.Terminal"
+            } else {
+                &app.config().identifier
+            });
+
             let settings_manager = SettingsManager::new();
             let initial_settings = settings_manager.load().unwrap_or_default();
             
             let state = Arc::new(AppState {
                 credentials_manager: CredentialsManager::new(),
                 settings_manager,
+                history_store: HistoryStore::new(),
                 http_client: reqwest::Client::new(),
-                usage: Mutex::new(None),
+                usage: Mutex::new(HashMap::new()),
+                errors: Mutex::new(HashMap::new()),
                 settings: Mutex::new(initial_settings.clone()),
-                last_notified_session: Mutex::new(None),
-                last_notified_weekly: Mutex::new(None),
+                notify_state: Mutex::new(NotifyState::load()),
+                refresh_now: Notify::new(),
+                usage_events: api::usage_events(),
+                api_server: Mutex::new(None),
+                api_error: Mutex::new(None),
+                metrics: Metrics::new(),
             });
 
             app.manage(state.clone());
 
-            let menu = create_tray_menu(app.handle(), None, &initial_settings)?;
+            let profiles = state.credentials_manager.load_profiles().unwrap_or_default();
+            let menu = create_tray_menu(app.handle(), &profiles, &HashMap::new(), &HashMap::new(), &initial_settings)?;
 
             let _tray = TrayIconBuilder::with_id(constants::TRAY_ID)
                 .title(constants::TRAY_TITLE_DEFAULT)
                 .menu(&menu)
                 .show_menu_on_left_click(true)
-                .on_menu_event(move |app, event| {
-                    match event.id().as_ref() {
-                        constants::menu::OPEN_CLAUDE => {
-                            let _ = open::that(constants::CLAUDE_URL);
-                        }
-                        constants::menu::REFRESH => {
-                            let app = app.clone();
-                            tauri::async_runtime::spawn(async move {
-                                let state = app.state::<Arc<AppState>>();
-                                do_refresh(&app, &state).await;
-                            });
-                        }
-                        constants::menu::SETTINGS => {
-                            if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                        constants::menu::QUIT => {
-                            app.exit(0);
-                        }
-                        _ => {}
-                    }
-                })
+                .on_menu_event(|app, event| handle_action(app, event.id().as_ref()))
                 .on_tray_icon_event(|_tray, event| {
                     if let TrayIconEvent::Click {
                         button: MouseButton::Left,
@@ -436,20 +1072,35 @@ pub fn run() {
             let app_handle = app.handle().clone();
             let state_clone = state.clone();
             tauri::async_runtime::spawn(async move {
-                do_refresh(&app_handle, &state_clone).await;
+                render(&app_handle, &state_clone).await;
             });
             
             start_auto_refresh(app.handle().clone(), state.clone());
+            start_config_watcher(app.handle().clone(), state.clone());
+            start_resume_watcher(app.handle().clone(), state.clone());
+            start_quiet_hours_watcher(app.handle().clone(), state.clone());
+            tauri::async_runtime::spawn(async move {
+                let _ = apply_api_settings(&state).await;
+            });
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_credentials,
             save_credentials,
+            get_profiles,
+            save_profile,
+            delete_profile,
+            set_active_profile,
+            discover_organizations,
             get_settings,
             save_settings,
             refresh_usage,
-            test_notification
+            get_usage_history,
+            get_window_history,
+            test_notification,
+            test_channel,
+            get_api_info
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
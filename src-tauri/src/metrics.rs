@@ -0,0 +1,133 @@
+use crate::constants::metrics::LATENCY_BUCKETS;
+use crate::error::FetchError;
+use crate::{UsageData, WindowKind};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Fetch outcomes and latencies of one organization
+#[derive(Debug, Clone, Default)]
+struct OrgFetches {
+    successes: u64,
+    /// Per [`FetchError::kind`]
+    failures: BTreeMap<&'static str, u64>,
+    /// Per bucket of [`LATENCY_BUCKETS`], not cumulative; the last entry counts slower fetches
+    buckets: Vec<u64>,
+    seconds: f64,
+}
+
+/// Counters for the Prometheus/`OpenMetrics` exporter, fed by every usage fetch
+#[derive(Debug, Default)]
+pub struct Metrics {
+    fetches: Mutex<BTreeMap<String, OrgFetches>>,
+    /// Organization of each profile seen so far, for the `org` label of the usage gauges
+    orgs: Mutex<HashMap<String, String>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one `fetch_usage` call for `profile` that took `elapsed`
+    pub fn record_fetch(&self, profile: &str, org_id: &str, result: &Result<UsageData, FetchError>, elapsed: Duration) {
+        self.orgs.lock().unwrap().insert(profile.to_string(), org_id.to_string());
+
+        let mut fetches = self.fetches.lock().unwrap();
+        let org = fetches.entry(org_id.to_string()).or_default();
+        match result {
+            Ok(_) => org.successes += 1,
+            Err(e) => *org.failures.entry(e.kind()).or_default() += 1,
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        org.buckets.resize(LATENCY_BUCKETS.len() + 1, 0);
+        let bucket = LATENCY_BUCKETS.iter().position(|le| seconds <= *le).unwrap_or(LATENCY_BUCKETS.len());
+        org.buckets[bucket] += 1;
+        org.seconds += seconds;
+    }
+
+    /// `OpenMetrics` exposition of the fetch counters and of `usage`, keyed by profile name.
+    /// Profiles that haven't been fetched since startup have no known org and are left out.
+    pub fn render(&self, usage: &HashMap<String, UsageData>, now: DateTime<Utc>) -> String {
+        let orgs = self.orgs.lock().unwrap().clone();
+        let mut windows: Vec<(&str, &str, &str, f64, Option<i64>)> = Vec::new();
+        for (profile, data) in usage {
+            let Some(org) = orgs.get(profile) else { continue };
+            for kind in [WindowKind::FiveHour, WindowKind::SevenDay] {
+                let Some(window) = data.window(kind) else { continue };
+                let until_reset = DateTime::parse_from_rfc3339(&window.resets_at)
+                    .ok()
+                    .map(|reset| (reset.with_timezone(&Utc) - now).num_seconds().max(0));
+                windows.push((org, profile, window_label(kind), window.utilization, until_reset));
+            }
+        }
+        windows.sort_by(|a, b| (a.0, a.1, a.2).cmp(&(b.0, b.1, b.2)));
+
+        let mut out = String::new();
+        family(&mut out, "seekers_utilization", "gauge", "Usage of the limit window in percent");
+        for (org, profile, window, utilization, _) in &windows {
+            let (org, profile) = (escape(org), escape(profile));
+            let _ = writeln!(out, "seekers_utilization{{window=\"{window}\",org=\"{org}\",profile=\"{profile}\"}} {utilization}");
+        }
+        family(&mut out, "seekers_seconds_until_reset", "gauge", "Seconds until the limit window resets");
+        for (org, profile, window, _, until_reset) in &windows {
+            if let Some(seconds) = until_reset {
+                let (org, profile) = (escape(org), escape(profile));
+                let _ = writeln!(
+                    out,
+                    "seekers_seconds_until_reset{{window=\"{window}\",org=\"{org}\",profile=\"{profile}\"}} {seconds}"
+                );
+            }
+        }
+
+        let fetches = self.fetches.lock().unwrap().clone();
+        family(&mut out, "seekers_fetch_success", "counter", "Successful usage fetches");
+        for (org, counts) in &fetches {
+            let _ = writeln!(out, "seekers_fetch_success_total{{org=\"{}\"}} {}", escape(org), counts.successes);
+        }
+        family(&mut out, "seekers_fetch_failure", "counter", "Failed usage fetches by reason");
+        for (org, counts) in &fetches {
+            for (reason, count) in &counts.failures {
+                let _ = writeln!(out, "seekers_fetch_failure_total{{org=\"{}\",reason=\"{reason}\"}} {count}", escape(org));
+            }
+        }
+        family(&mut out, "seekers_fetch_duration_seconds", "histogram", "Time taken by usage fetches");
+        for (org, counts) in &fetches {
+            let org = escape(org);
+            let mut cumulative = 0;
+            for (le, count) in LATENCY_BUCKETS.iter().zip(&counts.buckets) {
+                cumulative += count;
+                // OpenMetrics wants bounds in float form, `1.0` rather than `1`
+                let le = format!("{le:?}");
+                let _ = writeln!(out, "seekers_fetch_duration_seconds_bucket{{org=\"{org}\",le=\"{le}\"}} {cumulative}");
+            }
+            let total: u64 = counts.buckets.iter().sum();
+            let _ = writeln!(out, "seekers_fetch_duration_seconds_bucket{{org=\"{org}\",le=\"+Inf\"}} {total}");
+            let _ = writeln!(out, "seekers_fetch_duration_seconds_count{{org=\"{org}\"}} {total}");
+            let _ = writeln!(out, "seekers_fetch_duration_seconds_sum{{org=\"{org}\"}} {}", counts.seconds);
+        }
+
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn window_label(kind: WindowKind) -> &'static str {
+    match kind {
+        WindowKind::FiveHour => "five_hour",
+        WindowKind::SevenDay => "seven_day",
+    }
+}
+
+/// Label values escape backslashes, quotes and newlines
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
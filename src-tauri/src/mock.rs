@@ -0,0 +1,399 @@
+//! Scripted stand-in for the claude.ai usage API, used by the integration tests and the
+//! `seekers-mock` binary. Point the app at it with `SEEKERS_API_BASE=<base_url>`.
+
+use chrono::{Duration, SecondsFormat, Utc};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// One scripted answer to `GET /organizations/{id}/usage`
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// 200 with the given utilization per window; `None` omits the window
+    Usage {
+        five_hour: Option<f64>,
+        seven_day: Option<f64>,
+    },
+    /// 200 with both windows present but `resets_at: null`
+    NullResetsAt { five_hour: f64, seven_day: f64 },
+    /// 429, with an optional `Retry-After` header in seconds
+    RateLimited { retry_after: Option<u64> },
+    /// Any other status with an empty JSON error body (401, 403, 5xx, ...)
+    Status(u16),
+    /// 200 with a body that is not valid JSON
+    Malformed,
+    /// 302 to the login page, as claude.ai does for some rejected cookies
+    RedirectToLogin,
+}
+
+impl MockResponse {
+    pub fn usage(five_hour: f64, seven_day: f64) -> Self {
+        Self::Usage {
+            five_hour: Some(five_hour),
+            seven_day: Some(seven_day),
+        }
+    }
+
+    pub fn missing_windows() -> Self {
+        Self::Usage {
+            five_hour: None,
+            seven_day: None,
+        }
+    }
+
+    /// Parse a scenario name as accepted by `seekers-mock`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let response = match name {
+            "normal" => Self::usage(42.0, 18.0),
+            "high" => Self::usage(91.0, 64.0),
+            "missing-windows" => Self::missing_windows(),
+            "null-resets-at" => Self::NullResetsAt {
+                five_hour: 42.0,
+                seven_day: 18.0,
+            },
+            "unauthorized" => Self::Status(401),
+            "forbidden" => Self::Status(403),
+            "rate-limited" => Self::RateLimited { retry_after: Some(120) },
+            "server-error" => Self::Status(500),
+            "malformed" => Self::Malformed,
+            "login-redirect" => Self::RedirectToLogin,
+            _ => return None,
+        };
+        Some(response)
+    }
+
+    fn render(&self) -> (u16, Vec<(&'static str, String)>, String) {
+        let now = Utc::now();
+        let five_hour_reset = (now + Duration::hours(2)).to_rfc3339_opts(SecondsFormat::Micros, false);
+        let seven_day_reset = (now + Duration::days(3)).to_rfc3339_opts(SecondsFormat::Micros, false);
+
+        match self {
+            Self::Usage { five_hour, seven_day } => {
+                let window = |utilization: Option<f64>, resets_at: &str| {
+                    utilization.map(|u| json!({ "utilization": u, "resets_at": resets_at }))
+                };
+                let body = json!({
+                    "five_hour": window(*five_hour, &five_hour_reset),
+                    "seven_day": window(*seven_day, &seven_day_reset),
+                });
+                (200, Vec::new(), body.to_string())
+            }
+            Self::NullResetsAt { five_hour, seven_day } => {
+                let body = json!({
+                    "five_hour": { "utilization": five_hour, "resets_at": null },
+                    "seven_day": { "utilization": seven_day, "resets_at": null },
+                });
+                (200, Vec::new(), body.to_string())
+            }
+            Self::RateLimited { retry_after } => {
+                let headers = retry_after
+                    .map(|secs| vec![("Retry-After", secs.to_string())])
+                    .unwrap_or_default();
+                (429, headers, json!({ "error": "rate_limited" }).to_string())
+            }
+            Self::Status(status) => (*status, Vec::new(), json!({ "error": "mock" }).to_string()),
+            Self::Malformed => (200, Vec::new(), "{\"five_hour\": {\"utilization\": ".to_string()),
+            Self::RedirectToLogin => (302, vec![("Location", "/login".to_string())], String::new()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Script {
+    queue: VecDeque<MockResponse>,
+    /// Served again once the queue runs dry
+    last: Option<MockResponse>,
+    requests: usize,
+}
+
+impl Script {
+    fn next(&mut self) -> Option<MockResponse> {
+        self.requests += 1;
+        if let Some(response) = self.queue.pop_front() {
+            self.last = Some(response);
+        }
+        self.last.clone()
+    }
+}
+
+pub struct MockServer {
+    port: u16,
+    script: Arc<Mutex<Script>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Listen on an ephemeral loopback port, answering usage requests from `script` in order
+    pub async fn start(script: Vec<MockResponse>) -> std::io::Result<Self> {
+        Self::bind(0, script).await
+    }
+
+    pub async fn bind(port: u16, script: Vec<MockResponse>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        let port = listener.local_addr()?.port();
+        let script = Arc::new(Mutex::new(Script {
+            queue: script.into(),
+            ..Script::default()
+        }));
+
+        let served = script.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let script = served.clone();
+                tokio::spawn(async move {
+                    let _ = handle(stream, &script).await;
+                });
+            }
+        });
+
+        Ok(Self { port, script, task })
+    }
+
+    /// Value for `SEEKERS_API_BASE` / the `apiBaseUrl` setting
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}/api", self.port)
+    }
+
+    /// Queue more responses after the ones already scripted
+    pub fn push(&self, response: MockResponse) {
+        self.script.lock().unwrap().queue.push_back(response);
+    }
+
+    /// Number of usage requests served so far
+    pub fn requests(&self) -> usize {
+        self.script.lock().unwrap().requests
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle(mut stream: TcpStream, script: &Mutex<Script>) -> std::io::Result<()> {
+    let Some((head, _)) = read_head(&mut stream).await? else {
+        return Ok(());
+    };
+
+    let request = String::from_utf8_lossy(&head);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let is_usage = path
+        .strip_prefix("/api/organizations/")
+        .is_some_and(|rest| rest.ends_with("/usage"));
+
+    let (status, headers, body) = if is_usage {
+        let response = script.lock().unwrap().next();
+        response.map_or_else(
+            || (500, Vec::new(), "{\"error\":\"mock script is empty\"}".to_string()),
+            |r| r.render(),
+        )
+    } else if path == "/api/organizations" {
+        (200, Vec::new(), organizations().to_string())
+    } else if path.starts_with("/login") {
+        (200, Vec::new(), "<html>Log in to Claude</html>".to_string())
+    } else {
+        (404, Vec::new(), "{\"error\":\"not_found\"}".to_string())
+    };
+
+    respond(stream, status, &headers, &body).await
+}
+
+/// Read up to the end of the request headers; returns the head and whatever body bytes came with it
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok(Some((buf, rest)));
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+async fn respond(mut stream: TcpStream, status: u16, headers: &[(&str, String)], body: &str) -> std::io::Result<()> {
+    let mut response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        reason(status),
+        body.len()
+    );
+    for (name, value) in headers {
+        let _ = write!(response, "{name}: {value}\r\n");
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// A request received by [`WebhookSink`]
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl CapturedRequest {
+    /// Value of the first header called `name`, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct Sink {
+    /// Status codes answered in order; 200 once they run out
+    statuses: VecDeque<u16>,
+    /// `Retry-After` sent with every 429
+    retry_after: Option<u64>,
+    requests: Vec<CapturedRequest>,
+}
+
+/// Stand-in for webhook, Slack, ntfy and Gotify endpoints: records every request it receives
+pub struct WebhookSink {
+    port: u16,
+    sink: Arc<Mutex<Sink>>,
+    task: JoinHandle<()>,
+}
+
+impl WebhookSink {
+    /// Listen on an ephemeral loopback port, answering with `statuses` in order and 200 after that
+    pub async fn start(statuses: Vec<u16>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+        let sink = Arc::new(Mutex::new(Sink {
+            statuses: statuses.into(),
+            ..Sink::default()
+        }));
+
+        let recorded = sink.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let sink = recorded.clone();
+                tokio::spawn(async move {
+                    let _ = capture(stream, &sink).await;
+                });
+            }
+        });
+
+        Ok(Self { port, sink, task })
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{path}", self.port)
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.sink.lock().unwrap().requests.clone()
+    }
+
+    /// Send `Retry-After: <secs>` with every 429 from now on
+    pub fn set_retry_after(&self, secs: u64) {
+        self.sink.lock().unwrap().retry_after = Some(secs);
+    }
+}
+
+impl Drop for WebhookSink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn capture(mut stream: TcpStream, sink: &Mutex<Sink>) -> std::io::Result<()> {
+    let Some((head, mut body)) = read_head(&mut stream).await? else {
+        return Ok(());
+    };
+
+    let head = String::from_utf8_lossy(&head).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or("/").to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut chunk = [0u8; 1024];
+    while body.len() < length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    let (status, headers) = {
+        let mut sink = sink.lock().unwrap();
+        sink.requests.push(CapturedRequest {
+            method,
+            path,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        });
+        let status = sink.statuses.pop_front().unwrap_or(200);
+        let retry_after = sink.retry_after.filter(|_| status == 429);
+        (status, retry_after.map(|secs| vec![("Retry-After", secs.to_string())]).unwrap_or_default())
+    };
+
+    respond(stream, status, &headers, "{}").await
+}
+
+/// Fixed answer to `GET /organizations`: a Pro personal org and a Max team org
+fn organizations() -> serde_json::Value {
+    json!([
+        {
+            "uuid": "11111111-1111-1111-1111-111111111111",
+            "name": "Personal",
+            "capabilities": ["chat", "claude_pro"],
+            "rate_limit_tier": "default_claude_ai",
+        },
+        {
+            "uuid": "22222222-2222-2222-2222-222222222222",
+            "name": "Team Max",
+            "capabilities": ["chat", "claude_max"],
+            "rate_limit_tier": "default_claude_max_20x",
+        },
+    ])
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        302 => "Found",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Mock",
+    }
+}
@@ -0,0 +1,522 @@
+use crate::constants;
+use crate::display::format_duration;
+use crate::forecast::same_reset;
+use crate::paths;
+use crate::settings::AppSettings;
+use crate::{UsageData, UsageWindow, WindowKind};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+/// A notification that should be shown to the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alert {
+    pub title: String,
+    pub body: String,
+    /// The window a usage alert is about, which its Snooze action silences
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<AlertSource>,
+}
+
+/// Profile and window a usage alert was raised for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertSource {
+    pub profile: String,
+    pub window: WindowKind,
+}
+
+/// What makes a notification rule fire
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleCondition {
+    /// Utilization reaches `percent`, once per reset cycle
+    Crosses { percent: u32 },
+    /// Utilization reaches each of `percents` in turn, each once per reset cycle
+    Escalating { percents: Vec<u32> },
+    /// At the current pace the window hits 100% within `minutes`
+    ProjectedLimit { minutes: u32 },
+    /// The window rolled over into a new cycle, see [`rolled_over`]
+    Reset,
+    /// Utilization climbs faster than this many percentage points per hour
+    #[serde(rename_all = "camelCase")]
+    RateAbove { percent_per_hour: f64 },
+}
+
+/// Daily local time range during which a rule stays silent; may wrap past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// What happens to alerts raised during quiet hours
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuietMode {
+    /// Summarized in one notification when the quiet period ends
+    #[default]
+    Digest,
+    Drop,
+}
+
+/// Quiet time starting on each of `days`; a period past midnight ends on the next day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietPeriod {
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietPeriod {
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let (day, time) = (at.weekday(), at.time());
+        if self.start <= self.end {
+            self.days.contains(&day) && self.start <= time && time < self.end
+        } else {
+            (self.days.contains(&day) && time >= self.start) || (self.days.contains(&day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// Weekly do-not-disturb schedule applying to all notification rules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietSchedule {
+    pub enabled: bool,
+    pub mode: QuietMode,
+    pub periods: Vec<QuietPeriod>,
+}
+
+impl Default for QuietSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: QuietMode::Digest,
+            periods: vec![QuietPeriod {
+                days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun],
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or_default(),
+                end: NaiveTime::from_hms_opt(7, 0, 0).unwrap_or_default(),
+            }],
+        }
+    }
+}
+
+impl QuietSchedule {
+    /// Whether `now` falls in one of the periods, in local time
+    pub fn is_quiet(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&Local).naive_local();
+        self.enabled && self.periods.iter().any(|period| period.contains(local))
+    }
+}
+
+/// One notification rule from settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationRule {
+    /// Stable identifier the dedup state is kept under
+    pub id: String,
+    pub enabled: bool,
+    pub window: WindowKind,
+    pub condition: RuleCondition,
+    /// Minimum time between two alerts of this rule
+    pub cooldown_minutes: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+    /// Body template; `{window}`, `{pct}`, `{rate}`, `{limit}` and `{profile}` are filled in.
+    /// Empty uses the condition's default.
+    pub message: String,
+}
+
+impl Default for NotificationRule {
+    fn default() -> Self {
+        Self::crosses("session", WindowKind::FiveHour, 80)
+    }
+}
+
+impl NotificationRule {
+    /// Rule that fires when `window` reaches `percent`
+    pub fn crosses(id: &str, window: WindowKind, percent: u32) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            window,
+            condition: RuleCondition::Crosses { percent },
+            cooldown_minutes: 0,
+            quiet_hours: None,
+            message: String::new(),
+        }
+    }
+
+    /// Rule that fires as `window` reaches each of `percents`
+    pub fn escalating(id: &str, window: WindowKind, percents: &[u32]) -> Self {
+        Self {
+            condition: RuleCondition::Escalating { percents: percents.to_vec() },
+            ..Self::crosses(id, window, 0)
+        }
+    }
+
+    fn template(&self) -> &str {
+        if !self.message.trim().is_empty() {
+            return &self.message;
+        }
+        match self.condition {
+            RuleCondition::Crosses { .. } | RuleCondition::Escalating { .. } => "{window} usage at {pct}%",
+            RuleCondition::ProjectedLimit { .. } => "{window} limit {limit} at this pace",
+            RuleCondition::Reset => "Your Claude {window} limit has reset",
+            RuleCondition::RateAbove { .. } => "{window} usage climbing {rate}%/h",
+        }
+    }
+
+    fn title(&self) -> String {
+        let window = window_name(self.window);
+        match self.condition {
+            RuleCondition::Crosses { .. } | RuleCondition::Escalating { .. } => format!("Claude {window} Limit"),
+            RuleCondition::ProjectedLimit { .. } => format!("Claude {window} Limit Ahead"),
+            RuleCondition::Reset => format!("Claude {window} Reset"),
+            RuleCondition::RateAbove { .. } => format!("Claude {window} Usage Climbing"),
+        }
+    }
+}
+
+/// Payload of the `window-reset` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowResetEvent {
+    pub profile: String,
+    pub window: WindowKind,
+    /// When the new cycle resets in turn
+    pub resets_at: String,
+}
+
+/// Whether `window` starts a new cycle after the one ending at `previous_resets_at`: that time
+/// has passed and the reset time moved on. Usage may already be climbing again when the first
+/// fetch after the reset comes in, so it isn't looked at.
+pub fn rolled_over(previous_resets_at: &str, window: &UsageWindow, now: DateTime<Utc>) -> bool {
+    !same_reset(previous_resets_at, &window.resets_at)
+        && previous_resets_at.parse::<DateTime<Utc>>().is_ok_and(|resets_at| resets_at <= now)
+}
+
+/// Windows of `current` that rolled over since `previous` was fetched
+pub fn window_resets(previous: &UsageData, current: &UsageData, now: DateTime<Utc>) -> Vec<WindowKind> {
+    [WindowKind::FiveHour, WindowKind::SevenDay]
+        .into_iter()
+        .filter(|kind| match (previous.window(*kind), current.window(*kind)) {
+            (Some(before), Some(after)) => rolled_over(&before.resets_at, after, now),
+            _ => false,
+        })
+        .collect()
+}
+
+fn window_name(kind: WindowKind) -> &'static str {
+    match kind {
+        WindowKind::FiveHour => "Session",
+        WindowKind::SevenDay => "Weekly",
+    }
+}
+
+fn render(template: &str, kind: WindowKind, window: &UsageWindow, profile: &str, now: DateTime<Utc>) -> String {
+    let forecast = window.forecast.as_ref();
+    let limit = match forecast.and_then(|f| f.limit_at) {
+        Some(limit_at) if limit_at > now => format!("in {}", format_duration(limit_at - now)),
+        Some(_) => "any moment".to_string(),
+        None => "not in sight".to_string(),
+    };
+    template
+        .replace("{window}", window_name(kind))
+        .replace("{pct}", &(window.utilization.round() as i32).to_string())
+        .replace("{rate}", &forecast.map_or(0.0, |f| f.rate_per_hour).round().to_string())
+        .replace("{limit}", &limit)
+        .replace("{profile}", profile)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct RuleState {
+    /// Whether the condition held at the previous sample; rules fire on the transition
+    holding: bool,
+    /// `resets_at` of the cycle that `levels` belong to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cycle: Option<String>,
+    /// Thresholds already reached in this cycle
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    levels: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_fired: Option<DateTime<Utc>>,
+    /// Triggered while held back by the cooldown or quiet hours, to fire once they are over
+    pending: bool,
+}
+
+impl RuleState {
+    /// Whether `rule` newly triggers on this sample of `window`
+    fn trigger(&mut self, rule: &NotificationRule, window: &UsageWindow, reset: bool, now: DateTime<Utc>) -> bool {
+        let forecast = window.forecast.as_ref();
+        let holding = match &rule.condition {
+            RuleCondition::Crosses { percent } => return self.reach(std::slice::from_ref(percent), window),
+            RuleCondition::Escalating { percents } => return self.reach(percents, window),
+            RuleCondition::ProjectedLimit { minutes } => forecast
+                .and_then(|f| f.limit_at)
+                .is_some_and(|limit_at| limit_at - now <= chrono::Duration::minutes(i64::from(*minutes))),
+            RuleCondition::Reset => reset,
+            RuleCondition::RateAbove { percent_per_hour } => {
+                forecast.is_some_and(|f| f.rate_per_hour > *percent_per_hour)
+            }
+        };
+        let was_holding = std::mem::replace(&mut self.holding, holding);
+        holding && !was_holding
+    }
+
+    /// Whether an alert held back earlier is still worth sending. A reset stays true for the
+    /// rest of the cycle.
+    fn still_holds(&self, rule: &NotificationRule) -> bool {
+        self.holding || rule.condition == RuleCondition::Reset
+    }
+
+    /// Mark the `levels` that `window` has reached; `true` if any is new in this cycle.
+    /// Jumping past several levels at once triggers only once.
+    fn reach(&mut self, levels: &[u32], window: &UsageWindow) -> bool {
+        // claude.ai leaves out the reset time while the window is unused, so no cycle is running
+        if window.resets_at.is_empty() {
+            self.cycle = None;
+            self.levels.clear();
+            self.holding = false;
+            return false;
+        }
+
+        let pct = window.utilization.round() as u32;
+        let new_cycle = self.cycle.as_deref().is_none_or(|cycle| !same_reset(cycle, &window.resets_at));
+        if new_cycle {
+            self.cycle = Some(window.resets_at.clone());
+            self.levels.clear();
+        }
+
+        self.holding = levels.iter().any(|level| pct >= *level);
+        let reached: Vec<u32> = levels
+            .iter()
+            .copied()
+            .filter(|level| pct >= *level && !self.levels.contains(level))
+            .collect();
+        self.levels.extend(&reached);
+        !reached.is_empty()
+    }
+}
+
+/// What has already been notified, per profile. Saved to disk so restarts don't repeat alerts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotifyState {
+    /// Per profile, per rule ID
+    rules: HashMap<String, HashMap<String, RuleState>>,
+    /// Per profile, the `resets_at` last seen for each window, to notice rollovers
+    resets: HashMap<String, HashMap<WindowKind, String>>,
+    /// Alerts held back during quiet hours, for the digest
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    queued: Vec<Alert>,
+    /// Per profile, when each snoozed window may alert again
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    snoozed: HashMap<String, HashMap<WindowKind, DateTime<Utc>>>,
+}
+
+fn get_state_path() -> std::path::PathBuf {
+    paths::data_file(constants::NOTIFY_STATE_FILE)
+}
+
+impl NotifyState {
+    /// State saved by the previous run, or a fresh one if there is none
+    pub fn load() -> Self {
+        fs::read_to_string(get_state_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string(self)?;
+        fs::write(get_state_path(), json)?;
+        Ok(())
+    }
+
+    /// Carry what was notified for profile `from` over to its new name `to`
+    pub fn rename_profile(&mut self, from: &str, to: &str) {
+        if let Some(rules) = self.rules.remove(from) {
+            self.rules.insert(to.to_string(), rules);
+        }
+        if let Some(resets) = self.resets.remove(from) {
+            self.resets.insert(to.to_string(), resets);
+        }
+        if let Some(snoozed) = self.snoozed.remove(from) {
+            self.snoozed.insert(to.to_string(), snoozed);
+        }
+        for source in self.queued.iter_mut().filter_map(|alert| alert.source.as_mut()) {
+            if source.profile == from {
+                source.profile = to.to_string();
+            }
+        }
+    }
+
+    /// Forget what was notified for a deleted profile, so a new one by that name starts fresh
+    pub fn remove_profile(&mut self, name: &str) {
+        self.rules.remove(name);
+        self.resets.remove(name);
+        self.snoozed.remove(name);
+        self.queued.retain(|alert| alert.source.as_ref().is_none_or(|source| source.profile != name));
+    }
+
+    /// Keep `window` of `profile` from alerting until `until`
+    pub fn snooze(&mut self, profile: &str, window: WindowKind, until: DateTime<Utc>) {
+        self.snoozed.entry(profile.to_string()).or_default().insert(window, until);
+    }
+
+    pub fn is_snoozed(&self, profile: &str, window: WindowKind, now: DateTime<Utc>) -> bool {
+        self.snoozed
+            .get(profile)
+            .and_then(|windows| windows.get(&window))
+            .is_some_and(|until| now < *until)
+    }
+
+    /// The `alerts` to show now. During quiet hours they are queued for the digest or dropped.
+    pub fn hold(&mut self, alerts: Vec<Alert>, schedule: &QuietSchedule, now: DateTime<Utc>) -> Vec<Alert> {
+        if !schedule.is_quiet(now) {
+            return alerts;
+        }
+        if schedule.mode == QuietMode::Digest {
+            self.queued.extend(alerts);
+        }
+        Vec::new()
+    }
+
+    /// One notification summarizing the queued alerts, once quiet hours are over
+    pub fn release(&mut self, schedule: &QuietSchedule, now: DateTime<Utc>) -> Option<Alert> {
+        if self.queued.is_empty() || schedule.is_quiet(now) {
+            return None;
+        }
+        let queued = std::mem::take(&mut self.queued);
+        if schedule.mode == QuietMode::Drop {
+            return None;
+        }
+        if let [alert] = queued.as_slice() {
+            return Some(alert.clone());
+        }
+
+        let mut lines: Vec<String> = queued
+            .iter()
+            .take(constants::notify::DIGEST_MAX_LINES)
+            .map(|alert| alert.body.clone())
+            .collect();
+        if queued.len() > lines.len() {
+            lines.push(format!("…and {} more", queued.len() - lines.len()));
+        }
+        Some(Alert {
+            title: format!("{} Claude Alerts During Quiet Hours", queued.len()),
+            body: lines.join("\n"),
+            source: None,
+        })
+    }
+}
+
+/// One-time alert sent when a profile's session key is rejected
+pub fn session_expired(label: Option<&str>) -> Alert {
+    let whose = label.map(|name| format!(" for {name}")).unwrap_or_default();
+    Alert {
+        title: "Claude Session Expired".to_string(),
+        body: format!("Update the session key{whose} in Seekers Settings to resume tracking"),
+        source: None,
+    }
+}
+
+/// Alert for a config file that was edited outside the app and could not be applied
+pub fn config_error(file: &str, message: &str) -> Alert {
+    Alert {
+        title: "Seekers Config Error".to_string(),
+        body: format!("{file} was not applied: {message}"),
+        source: None,
+    }
+}
+
+/// Decide which alerts a fresh `usage` sample for `profile` triggers.
+///
+/// A rule fires when its condition starts to hold, or for thresholds when a level is first
+/// reached in the window's reset cycle. An alert held back by the rule's cooldown or quiet
+/// hours fires once they are over if the condition still holds; one for a snoozed window is
+/// used up. `label` is appended to the body when several profiles are tracked.
+pub fn evaluate(
+    state: &mut NotifyState,
+    profile: &str,
+    label: Option<&str>,
+    usage: &UsageData,
+    settings: &AppSettings,
+    now: DateTime<Utc>,
+) -> Vec<Alert> {
+    let suffix = label.map(|name| format!(" ({name})")).unwrap_or_default();
+    let windows = [
+        (WindowKind::FiveHour, usage.five_hour.as_ref()),
+        (WindowKind::SevenDay, usage.seven_day.as_ref()),
+    ];
+
+    let seen = state.resets.entry(profile.to_string()).or_default();
+    let mut reset = HashMap::new();
+    for (kind, window) in windows {
+        let Some(window) = window else { continue };
+        let previous = seen.insert(kind, window.resets_at.clone());
+        reset.insert(kind, previous.is_some_and(|previous| rolled_over(&previous, window, now)));
+    }
+
+    state.snoozed.retain(|_, windows| {
+        windows.retain(|_, until| now < *until);
+        !windows.is_empty()
+    });
+    let snoozed: Vec<WindowKind> = windows
+        .iter()
+        .map(|(kind, _)| *kind)
+        .filter(|kind| state.is_snoozed(profile, *kind, now))
+        .collect();
+
+    let rule_states = state.rules.entry(profile.to_string()).or_default();
+    rule_states.retain(|id, _| settings.notification_rules.iter().any(|rule| &rule.id == id));
+
+    let mut alerts = Vec::new();
+    for rule in settings.notification_rules.iter().filter(|rule| rule.enabled) {
+        let Some(window) = windows.iter().find(|(kind, _)| *kind == rule.window).and_then(|(_, w)| *w) else {
+            continue;
+        };
+        let reset = reset.get(&rule.window).copied().unwrap_or(false);
+
+        let rule_state = rule_states.entry(rule.id.clone()).or_default();
+        let triggered = rule_state.trigger(rule, window, reset, now);
+
+        let cooling = rule_state.last_fired.is_some_and(|fired| {
+            now - fired < chrono::Duration::minutes(i64::from(rule.cooldown_minutes))
+        });
+        let quiet = rule.quiet_hours.is_some_and(|q| q.contains(now.with_timezone(&Local).time()));
+        if cooling || quiet {
+            rule_state.pending |= triggered;
+            continue;
+        }
+        let pending = std::mem::take(&mut rule_state.pending) && rule_state.still_holds(rule);
+        if !(triggered || pending) || snoozed.contains(&rule.window) {
+            continue;
+        }
+
+        rule_state.last_fired = Some(now);
+        alerts.push(Alert {
+            title: rule.title(),
+            body: format!("{}{suffix}", render(rule.template(), rule.window, window, label.unwrap_or(profile), now)),
+            source: Some(AlertSource {
+                profile: profile.to_string(),
+                window: rule.window,
+            }),
+        });
+    }
+
+    alerts
+}
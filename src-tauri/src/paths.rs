@@ -0,0 +1,136 @@
+use crate::constants;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static DIRS: OnceLock<Dirs> = OnceLock::new();
+
+/// Where Seekers keeps its files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirs {
+    /// `settings.json` and `credentials.json`
+    pub config: PathBuf,
+    /// Usage history
+    pub data: PathBuf,
+    /// The config directory was given explicitly and holds everything for this instance
+    pub isolated: bool,
+}
+
+impl Dirs {
+    /// Resolve from an explicit `--config-dir` and the environment.
+    ///
+    /// An explicit directory (the flag, then `SEEKERS_CONFIG_DIR`) holds both config and data
+    /// so parallel instances never collide. Otherwise the XDG base directories are used,
+    /// defaulting to `~/.config` and `~/.local/share`.
+    pub fn resolve(config_dir: Option<PathBuf>, env: impl Fn(&str) -> Option<OsString>) -> Self {
+        let var = |name: &str| env(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+        if let Some(dir) = config_dir.or_else(|| var(constants::CONFIG_DIR_ENV)) {
+            return Self {
+                data: dir.clone(),
+                config: dir,
+                isolated: true,
+            };
+        }
+
+        let home = var("HOME")
+            .or_else(std::env::home_dir)
+            .unwrap_or_else(std::env::temp_dir);
+        // Relative XDG paths are invalid per the spec and ignored
+        let base = |xdg: &str, default: &[&str]| {
+            var(xdg)
+                .filter(|p| p.is_absolute())
+                .unwrap_or_else(|| default.iter().fold(home.clone(), |path, part| path.join(part)))
+                .join(constants::CONFIG_DIR_NAME)
+        };
+
+        Self {
+            config: base("XDG_CONFIG_HOME", &[".config"]),
+            data: base("XDG_DATA_HOME", &[".local", "share"]),
+            isolated: false,
+        }
+    }
+}
+
+/// Where versions before the XDG layout kept everything, whatever `XDG_CONFIG_HOME` says
+pub fn legacy_dir(env: impl Fn(&str) -> Option<OsString>) -> PathBuf {
+    env("HOME")
+        .filter(|v| !v.is_empty())
+        .map_or_else(|| PathBuf::from("."), PathBuf::from)
+        .join(".config")
+        .join(constants::CONFIG_DIR_NAME)
+}
+
+/// Move files left in `legacy` by earlier versions to where `dirs` expects them, without
+/// replacing anything already there. Isolated instances never had a legacy directory.
+/// Returns the paths the files were moved to.
+pub fn migrate_legacy(legacy: &Path, dirs: &Dirs) -> Vec<PathBuf> {
+    if dirs.isolated {
+        return Vec::new();
+    }
+
+    let files = [
+        (constants::SETTINGS_FILE, &dirs.config),
+        (constants::CREDENTIALS_FILE, &dirs.config),
+        (constants::HISTORY_FILE, &dirs.data),
+    ];
+    let mut moved = Vec::new();
+    for (name, dir) in files {
+        let from = legacy.join(name);
+        let to = dir.join(name);
+        if from == to || !from.exists() || to.exists() || fs::create_dir_all(dir).is_err() {
+            continue;
+        }
+        let renamed = fs::rename(&from, &to).is_ok()
+            || (fs::copy(&from, &to).is_ok() && fs::remove_file(&from).is_ok());
+        if renamed {
+            moved.push(to);
+        }
+    }
+    moved
+}
+
+fn resolve_from_env(config_dir: Option<PathBuf>) -> Dirs {
+    let env = |name: &str| std::env::var_os(name);
+    let dirs = Dirs::resolve(config_dir, env);
+    migrate_legacy(&legacy_dir(env), &dirs);
+    dirs
+}
+
+/// Fix the directories, honoring a `--config-dir` flag, and move over files from the legacy
+/// directory. Must run before any file is touched; returns `false` if they were already resolved.
+pub fn init(config_dir: Option<PathBuf>) -> bool {
+    DIRS.get().is_none() && DIRS.set(resolve_from_env(config_dir)).is_ok()
+}
+
+pub fn dirs() -> &'static Dirs {
+    DIRS.get_or_init(|| resolve_from_env(None))
+}
+
+fn file_in(dir: &Path, name: &str) -> PathBuf {
+    fs::create_dir_all(dir).ok();
+    dir.join(name)
+}
+
+pub fn config_file(name: &str) -> PathBuf {
+    file_in(&dirs().config, name)
+}
+
+pub fn data_file(name: &str) -> PathBuf {
+    file_in(&dirs().data, name)
+}
+
+/// Value of `--config-dir <DIR>` or `--config-dir=<DIR>` among `args`
+pub fn config_dir_arg(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config-dir" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(dir) = arg.strip_prefix("--config-dir=") {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    None
+}
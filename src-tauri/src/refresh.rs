@@ -0,0 +1,104 @@
+use crate::credentials::Profile;
+use crate::error::FetchError;
+use crate::history::{HistoryStore, UsageSample};
+use crate::metrics::Metrics;
+use crate::notify::{self, Alert, NotifyState, WindowResetEvent};
+use crate::settings::AppSettings;
+use crate::{claude, forecast, UsageData};
+use chrono::Utc;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+/// The state one profile's refresh reads and updates, borrowed from the app
+pub struct Pipeline<'a> {
+    pub client: &'a reqwest::Client,
+    pub settings: &'a Mutex<AppSettings>,
+    pub history: &'a HistoryStore,
+    pub metrics: &'a Metrics,
+    /// Latest usage per profile name
+    pub usage: &'a Mutex<HashMap<String, UsageData>>,
+    /// Last fetch failure per profile name
+    pub errors: &'a Mutex<HashMap<String, FetchError>>,
+    pub notify_state: &'a Mutex<NotifyState>,
+}
+
+/// What a successful refresh leaves for the app to show
+#[derive(Debug, Clone)]
+pub struct Refreshed {
+    /// Usage as stored, with its forecast
+    pub usage: UsageData,
+    /// Windows that rolled over since the previous refresh
+    pub resets: Vec<WindowResetEvent>,
+    /// Alerts to show now, after quiet hours have held back or released theirs
+    pub alerts: Vec<Alert>,
+}
+
+impl Pipeline<'_> {
+    /// Fetch `profile`'s usage, record it and work out which alerts it raises. `label` names
+    /// the profile in alerts. A failure is kept as the profile's error and returned.
+    pub async fn refresh(&self, profile: &Profile, label: Option<&str>) -> Result<Refreshed, FetchError> {
+        let base_url = claude::api_base(self.settings.lock().await.api_base_url.as_deref());
+        let started = std::time::Instant::now();
+        let result = claude::fetch_usage(&base_url, &profile.org_id, &profile.session_key, self.client).await;
+        self.metrics.record_fetch(&profile.name, &profile.org_id, &result, started.elapsed());
+
+        let usage = match result {
+            Ok(usage) => {
+                self.errors.lock().await.remove(&profile.name);
+                usage
+            }
+            Err(e) => {
+                self.errors.lock().await.insert(profile.name.clone(), e.clone());
+                return Err(e);
+            }
+        };
+        let previous = self.usage.lock().await.get(&profile.name).cloned();
+        let usage = self.store(&profile.name, usage).await;
+
+        let resets = previous
+            .map(|p| notify::window_resets(&p, &usage, Utc::now()))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|window| WindowResetEvent {
+                profile: profile.name.clone(),
+                window,
+                resets_at: usage.window(window).map(|w| w.resets_at.clone()).unwrap_or_default(),
+            })
+            .collect();
+
+        let settings = self.settings.lock().await;
+        let alerts = self.alerts(&profile.name, label, &usage, &settings).await;
+
+        Ok(Refreshed { usage, resets, alerts })
+    }
+
+    /// Append `usage` to the history, fill in its forecast and keep it as the profile's latest
+    async fn store(&self, profile: &str, mut usage: UsageData) -> UsageData {
+        let now = Utc::now();
+        let _ = self.history.append(&UsageSample::from_usage(profile, &usage, now));
+
+        if let Ok(samples) = self.history.query(Some(profile), Some(forecast::history_since(now)), None) {
+            forecast::apply(&mut usage, &samples);
+        }
+
+        self.usage.lock().await.insert(profile.to_string(), usage.clone());
+        usage
+    }
+
+    /// Alerts `usage` raises, minus those quiet hours hold back, plus a digest due now
+    async fn alerts(
+        &self,
+        profile: &str,
+        label: Option<&str>,
+        usage: &UsageData,
+        settings: &AppSettings,
+    ) -> Vec<Alert> {
+        let mut notify_state = self.notify_state.lock().await;
+        let now = Utc::now();
+        let alerts = notify::evaluate(&mut notify_state, profile, label, usage, settings, now);
+        let mut alerts = notify_state.hold(alerts, &settings.quiet_hours, now);
+        alerts.extend(notify_state.release(&settings.quiet_hours, now));
+        let _ = notify_state.save();
+        alerts
+    }
+}
@@ -0,0 +1,79 @@
+use crate::constants::resume;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::net::TcpStream;
+
+/// Detects suspend/resume by comparing the wall clock against the monotonic clock.
+///
+/// The monotonic clock stops while the machine sleeps but the wall clock does not, so after
+/// waking the wall clock has run ahead by roughly the time spent asleep.
+pub struct ClockWatch {
+    instant: Instant,
+    wall: SystemTime,
+}
+
+impl Default for ClockWatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockWatch {
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now(), SystemTime::now())
+    }
+
+    pub fn starting_at(instant: Instant, wall: SystemTime) -> Self {
+        Self { instant, wall }
+    }
+
+    pub fn check(&mut self) -> Option<Duration> {
+        self.check_at(Instant::now(), SystemTime::now())
+    }
+
+    /// How far the wall clock jumped ahead since the last check, if that looks like a wake-up.
+    /// Clocks set backwards are ignored.
+    pub fn check_at(&mut self, instant: Instant, wall: SystemTime) -> Option<Duration> {
+        let elapsed = instant.saturating_duration_since(self.instant);
+        let wall_elapsed = wall.duration_since(self.wall).unwrap_or_default();
+        self.instant = instant;
+        self.wall = wall;
+
+        let jump = wall_elapsed.saturating_sub(elapsed);
+        (jump >= Duration::from_secs(resume::CLOCK_JUMP_SECS)).then_some(jump)
+    }
+}
+
+/// Tracks whether claude.ai is reachable, to refresh as soon as the connection comes back
+#[derive(Default)]
+pub struct NetworkWatch {
+    unreachable: bool,
+}
+
+impl NetworkWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the latest probe; `true` when it is the first success after a failure
+    pub fn update(&mut self, reachable: bool) -> bool {
+        let reconnected = reachable && self.unreachable;
+        self.unreachable = !reachable;
+        reconnected
+    }
+}
+
+/// Whether a TCP connection to the host of `base_url` can be opened
+pub async fn reachable(base_url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(base_url) else {
+        return false;
+    };
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return false;
+    };
+
+    let connect = TcpStream::connect((host, port));
+    matches!(
+        tokio::time::timeout(Duration::from_secs(resume::PROBE_TIMEOUT_SECS), connect).await,
+        Ok(Ok(_))
+    )
+}
@@ -0,0 +1,108 @@
+use crate::constants::schedule;
+use crate::error::FetchError;
+use crate::{UsageData, UsageWindow};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Why the scheduler picked the delay it did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    /// The configured refresh interval
+    Normal,
+    /// Utilization is climbing fast or a window resets soon
+    Fast,
+    /// Nothing has moved lately
+    Slow,
+    /// Transient failures in a row, waiting exponentially longer
+    Backoff,
+    /// claude.ai asked us to wait via `Retry-After`
+    RetryAfter,
+}
+
+/// Decides how long to wait before the next automatic refresh
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    failures: u32,
+}
+
+/// Failures worth retrying later; a rejected key or unknown org won't fix itself
+fn is_transient(error: &FetchError) -> bool {
+    !matches!(error, FetchError::SessionExpired | FetchError::OrgNotFound)
+}
+
+fn resets_within(window: &UsageWindow, now: DateTime<Utc>, minutes: i64) -> bool {
+    window.utilization > 0.0
+        && window
+            .resets_at
+            .parse::<DateTime<Utc>>()
+            .is_ok_and(|resets_at| resets_at > now && resets_at - now <= chrono::Duration::minutes(minutes))
+}
+
+/// Time until just after the earliest upcoming reset of a window that is in use
+pub fn until_next_reset<'a>(usage: impl IntoIterator<Item = &'a UsageData>, now: DateTime<Utc>) -> Option<Duration> {
+    usage
+        .into_iter()
+        .flat_map(|u| [u.five_hour.as_ref(), u.seven_day.as_ref()])
+        .flatten()
+        .filter(|w| w.utilization > 0.0)
+        .filter_map(|w| w.resets_at.parse::<DateTime<Utc>>().ok())
+        .filter(|resets_at| *resets_at > now)
+        .min()
+        .and_then(|resets_at| (resets_at - now).to_std().ok())
+        .map(|wait| wait + Duration::from_secs(schedule::RESET_GRACE_SECS))
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay before the next refresh, given the `interval` from settings, the errors of the
+    /// refresh that just ran and the latest usage of every profile
+    pub fn next_delay<'a>(
+        &mut self,
+        interval: Duration,
+        errors: &[FetchError],
+        usage: impl IntoIterator<Item = &'a UsageData>,
+        now: DateTime<Utc>,
+    ) -> (Duration, Pace) {
+        let transient: Vec<_> = errors.iter().filter(|e| is_transient(e)).collect();
+        if !transient.is_empty() {
+            // The first failure waits the normal interval, each one after it twice as long
+            let doublings = self.failures.min(schedule::MAX_BACKOFF_DOUBLINGS);
+            self.failures = self.failures.saturating_add(1);
+            let ceiling = interval.max(Duration::from_secs(schedule::MAX_BACKOFF_SECS));
+            let backoff = interval.saturating_mul(2u32.saturating_pow(doublings)).min(ceiling);
+
+            let retry_after = transient.iter().filter_map(|e| e.retry_after()).max();
+            return match retry_after {
+                Some(secs) => (Duration::from_secs(secs).max(interval), Pace::RetryAfter),
+                None => (backoff, Pace::Backoff),
+            };
+        }
+        self.failures = 0;
+
+        let windows: Vec<&UsageWindow> = usage
+            .into_iter()
+            .flat_map(|u| [u.five_hour.as_ref(), u.seven_day.as_ref()])
+            .flatten()
+            .collect();
+        let rate = |w: &UsageWindow| w.forecast.as_ref().map(|f| f.rate_per_hour);
+
+        let climbing = windows.iter().any(|w| rate(w).is_some_and(|r| r >= schedule::FAST_RATE_PER_HOUR));
+        let resetting = windows.iter().any(|w| resets_within(w, now, schedule::RESET_SOON_MINUTES));
+        if climbing || resetting {
+            let fast = (interval / schedule::SPEEDUP_DIVISOR).max(Duration::from_secs(schedule::MIN_INTERVAL_SECS));
+            return (fast.min(interval), Pace::Fast);
+        }
+
+        let idle = !windows.is_empty()
+            && windows.iter().all(|w| rate(w).is_some_and(|r| r.abs() <= schedule::IDLE_RATE_PER_HOUR));
+        if idle {
+            let slow = (interval * schedule::SLOWDOWN_FACTOR).min(Duration::from_secs(schedule::MAX_IDLE_INTERVAL_SECS));
+            return (slow.max(interval), Pace::Slow);
+        }
+
+        (interval, Pace::Normal)
+    }
+}
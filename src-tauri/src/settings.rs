@@ -1,65 +1,301 @@
+use crate::api::ApiSettings;
+use crate::channels::{ChannelConfig, ChannelSecret};
 use crate::constants;
+use crate::credential_store::{self, CredentialStore};
+use crate::notify::{NotificationRule, QuietSchedule, RuleCondition};
+use crate::paths;
+use crate::WindowKind;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
 fn get_settings_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let config_dir = PathBuf::from(home)
-        .join(".config")
-        .join(constants::CONFIG_DIR_NAME);
-    fs::create_dir_all(&config_dir).ok();
-    config_dir.join(constants::SETTINGS_FILE)
+    paths::config_file(constants::SETTINGS_FILE)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+/// Which numbers the menu bar title shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MenuBarDisplay {
+    #[default]
+    Session,
+    Weekly,
+    Both,
+    Higher,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressStyle {
+    #[default]
+    Circles,
+    Blocks,
+    Bar,
+    Dots,
+}
+
+/// Which profile the menu bar title reflects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayProfile {
+    #[default]
+    Active,
+    /// Highest utilization across all profiles
+    Aggregate,
+}
+
+/// How the menu bar title shows that its numbers are stale
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleMarker {
+    /// Trailing question mark: `42%?`
+    #[default]
+    Question,
+    /// Parentheses, which read as dimmed: `(42%)`
+    Parentheses,
+    Off,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct AppSettings {
-    pub menu_bar_display: String,
+    /// Schema version, see [`MIGRATIONS`]
+    pub version: u32,
+    pub menu_bar_display: MenuBarDisplay,
     pub show_percent_symbol: bool,
-    pub progress_style: String,
+    pub progress_style: ProgressStyle,
     pub progress_length: u8,
     pub refresh_interval: u32,
-    pub notify_session: u32,
-    pub notify_weekly: u32,
+    pub notification_rules: Vec<NotificationRule>,
+    /// Weekly do-not-disturb schedule for the notification rules
+    pub quiet_hours: QuietSchedule,
+    /// Webhooks and push services that receive every alert besides the desktop notification
+    pub channels: Vec<ChannelConfig>,
+    pub tray_profile: TrayProfile,
+    pub stale_marker: StaleMarker,
+    /// Usage older than this many refresh intervals is marked stale
+    pub stale_after_intervals: u32,
+    /// Loopback HTTP API for other local tools
+    pub api: ApiSettings,
+    /// Overrides the claude.ai API base URL, mainly for testing against `seekers-mock`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
-            menu_bar_display: "session".to_string(),
+            version: CURRENT_VERSION,
+            menu_bar_display: MenuBarDisplay::Session,
             show_percent_symbol: true,
-            progress_style: "circles".to_string(),
+            progress_style: ProgressStyle::Circles,
             progress_length: 10,
             refresh_interval: 15,
-            notify_session: 80,
-            notify_weekly: 80,
+            notification_rules: vec![
+                NotificationRule::escalating("session", WindowKind::FiveHour, &[50, 75, 90, 100]),
+                NotificationRule::escalating("weekly", WindowKind::SevenDay, &[50, 75, 90, 100]),
+                NotificationRule {
+                    enabled: false,
+                    condition: RuleCondition::Reset,
+                    ..NotificationRule::crosses("session-reset", WindowKind::FiveHour, 0)
+                },
+            ],
+            quiet_hours: QuietSchedule::default(),
+            channels: Vec::new(),
+            tray_profile: TrayProfile::Active,
+            stale_marker: StaleMarker::Question,
+            stale_after_intervals: 3,
+            api: ApiSettings::default(),
+            api_base_url: None,
+        }
+    }
+}
+
+/// Forward migrations; `MIGRATIONS[n]` upgrades a version `n + 1` file to version `n + 2`.
+/// Files written before versioning have no `version` field and count as version 1.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v1_to_v2, v2_to_v3];
+
+pub const CURRENT_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Version 1 stored the display options as free strings matched case-sensitively
+fn v1_to_v2(settings: &mut Map<String, Value>) {
+    for key in ["menuBarDisplay", "progressStyle", "trayProfile"] {
+        if let Some(Value::String(value)) = settings.get_mut(key) {
+            *value = value.trim().to_lowercase();
+        }
+    }
+}
+
+/// Version 2 had one threshold per window (0 meaning off); each becomes a "crosses" rule
+fn v2_to_v3(settings: &mut Map<String, Value>) {
+    let thresholds = [
+        ("notifySession", "session", WindowKind::FiveHour),
+        ("notifyWeekly", "weekly", WindowKind::SevenDay),
+    ];
+    let mut rules = Vec::new();
+    for (key, id, window) in thresholds {
+        let threshold = settings.remove(key).and_then(|v| v.as_u64()).map_or(80, |v| v as u32);
+        let mut rule = NotificationRule::crosses(id, window, if threshold > 0 { threshold } else { 80 });
+        rule.enabled = threshold > 0;
+        rules.extend(serde_json::to_value(rule).ok());
+    }
+    settings.entry("notificationRules").or_insert(Value::Array(rules));
+}
+
+impl AppSettings {
+    /// Parse a settings file of any version.
+    ///
+    /// Older files are migrated forward, missing fields take their defaults and fields with
+    /// invalid values are reset individually, so only malformed JSON is an error.
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        Self::parse_over(json, &Self::default()).map(|(settings, _)| settings)
+    }
+
+    /// Like [`parse`](Self::parse), but fields with invalid values keep their value from
+    /// `current`. Those fields are returned too, each with the reason it was rejected.
+    pub fn parse_over(json: &str, current: &Self) -> Result<(Self, Vec<String>), serde_json::Error> {
+        let mut value: Value = serde_json::from_str(json)?;
+        let Value::Object(map) = &mut value else {
+            return Ok((Self::default(), Vec::new()));
+        };
+
+        let version = map.get("version").and_then(Value::as_u64).unwrap_or(1) as usize;
+        for migrate in MIGRATIONS.iter().skip(version.saturating_sub(1)) {
+            migrate(map);
+        }
+
+        let current = serde_json::to_value(current)?;
+        let mut rejected = Vec::new();
+        for (key, field) in map.iter_mut() {
+            let probe = Value::Object(Map::from_iter([(key.clone(), field.clone())]));
+            if let Err(e) = serde_json::from_value::<Self>(probe) {
+                rejected.push(format!("{key} ({e})"));
+                *field = current.get(key).cloned().unwrap_or(Value::Null);
+            }
+        }
+        map.retain(|_, field| !field.is_null());
+
+        let mut settings: Self = serde_json::from_value(value)?;
+        settings.version = settings.version.max(CURRENT_VERSION);
+        settings.assign_channel_ids();
+        Ok((settings, rejected))
+    }
+
+    /// Give channels without an ID, e.g. from an earlier version, one of their own
+    fn assign_channel_ids(&mut self) {
+        let mut next = 1;
+        for i in 0..self.channels.len() {
+            let id = &self.channels[i].id;
+            if !id.is_empty() && !self.channels[..i].iter().any(|c| &c.id == id) {
+                continue;
+            }
+            while self.channels.iter().any(|c| c.id == format!("channel-{next}")) {
+                next += 1;
+            }
+            self.channels[i].id = format!("channel-{next}");
+        }
+    }
+
+    /// Take the channel URLs and tokens out, by channel ID, to be stored apart from `settings.json`
+    pub fn take_channel_secrets(&mut self) -> HashMap<String, ChannelSecret> {
+        self.channels
+            .iter_mut()
+            .map(|channel| {
+                let secret = ChannelSecret {
+                    url: std::mem::take(&mut channel.url),
+                    token: channel.token.take(),
+                };
+                (channel.id.clone(), secret)
+            })
+            .collect()
+    }
+
+    /// Put back what [`take_channel_secrets`](Self::take_channel_secrets) took out. A URL or
+    /// token still in the settings, e.g. typed into `settings.json`, wins.
+    pub fn apply_channel_secrets(&mut self, secrets: &HashMap<String, ChannelSecret>) {
+        for channel in &mut self.channels {
+            let Some(secret) = secrets.get(&channel.id) else { continue };
+            if channel.url.is_empty() {
+                channel.url.clone_from(&secret.url);
+            }
+            if channel.token.is_none() {
+                channel.token.clone_from(&secret.token);
+            }
         }
     }
 }
 
-pub struct SettingsManager;
+/// Reads and writes `settings.json`, keeping the channel URLs and tokens in a credential store
+pub struct SettingsManager {
+    secrets: Box<dyn CredentialStore>,
+}
+
+impl Default for SettingsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl SettingsManager {
     pub fn new() -> Self {
-        Self
+        Self {
+            secrets: credential_store::channel_store(),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        get_settings_path()
     }
 
     pub fn save(&self, settings: &AppSettings) -> Result<(), Box<dyn std::error::Error>> {
+        let mut settings = settings.clone();
+        settings.assign_channel_ids();
+        let secrets = settings.take_channel_secrets();
+        if secrets.is_empty() {
+            self.secrets.clear()?;
+        } else {
+            self.secrets.write(&serde_json::to_string(&secrets)?)?;
+        }
+
         let path = get_settings_path();
-        let json = serde_json::to_string_pretty(settings)?;
+        let json = serde_json::to_string_pretty(&settings)?;
         fs::write(&path, &json)?;
         Ok(())
     }
 
+    /// Load and migrate the settings file.
+    ///
+    /// A file that isn't valid JSON is copied aside first, so falling back to defaults
+    /// and saving later never destroys it.
     pub fn load(&self) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        self.load_over(&AppSettings::default()).map(|(settings, _)| settings)
+    }
+
+    /// Load like [`load`](Self::load), keeping `current` values for invalid fields, see
+    /// [`AppSettings::parse_over`].
+    ///
+    /// Never writes: channel URLs and tokens found in the file, written by an earlier version or
+    /// by hand, are used as they are and only move to the credential store on the next [`save`](Self::save).
+    pub fn load_over(&self, current: &AppSettings) -> Result<(AppSettings, Vec<String>), Box<dyn std::error::Error>> {
         let path = get_settings_path();
         if !path.exists() {
-            return Ok(AppSettings::default());
+            return Ok((AppSettings::default(), Vec::new()));
         }
 
         let json = fs::read_to_string(&path)?;
-        let settings: AppSettings = serde_json::from_str(&json)?;
-        Ok(settings)
+        let (mut settings, rejected) = AppSettings::parse_over(&json, current).map_err(|e| {
+            let backup = path.with_extension(constants::SETTINGS_BACKUP_EXTENSION);
+            fs::copy(&path, &backup).ok();
+            format!("{} is not valid JSON ({e}); a copy was kept at {}", path.display(), backup.display())
+        })?;
+
+        if !settings.channels.is_empty() {
+            // Settings without channel URLs still beat no settings at all
+            let stored = self.secrets.read().ok().flatten().unwrap_or_default();
+            settings.apply_channel_secrets(&serde_json::from_str(&stored).unwrap_or_default());
+        }
+        Ok((settings, rejected))
     }
 }
@@ -0,0 +1,36 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Detects edits to a file by polling its modification time and size
+pub struct FileWatch {
+    path: PathBuf,
+    last: Option<(SystemTime, u64)>,
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), metadata.len()))
+}
+
+impl FileWatch {
+    /// Starts from the file's current state, so only later changes are reported
+    pub fn new(path: PathBuf) -> Self {
+        let last = stamp(&path);
+        Self { path, last }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the file was created, modified or removed since the last call
+    pub fn changed(&mut self) -> bool {
+        let current = stamp(&self.path);
+        if current == self.last {
+            return false;
+        }
+        self.last = current;
+        true
+    }
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use seekers_lib::api::{self, ApiServer, ApiSource};
+use seekers_lib::history::UsageSample;
+use seekers_lib::{UsageData, UsageWindow};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+const TOKEN: &str = "0123456789abcdef";
+
+type HistoryQuery = (Option<String>, Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+#[derive(Default)]
+struct Fixture {
+    usage: HashMap<String, UsageData>,
+    queries: Mutex<Vec<HistoryQuery>>,
+    refreshes: AtomicUsize,
+    events: Option<broadcast::Sender<UsageData>>,
+    metrics: Option<String>,
+}
+
+impl ApiSource for Fixture {
+    async fn usage(&self) -> HashMap<String, UsageData> {
+        self.usage.clone()
+    }
+
+    fn history(
+        &self,
+        profile: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageSample>, String> {
+        self.queries.lock().unwrap().push((profile.map(str::to_string), from, to));
+        let usage = self.usage.get(profile.unwrap_or("Default")).cloned().unwrap_or_else(|| sample(0.0));
+        Ok(vec![UsageSample::from_usage(profile.unwrap_or("Default"), &usage, Utc::now())])
+    }
+
+    fn refresh(&self) {
+        self.refreshes.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<UsageData> {
+        self.events.as_ref().expect("fixture has no event sender").subscribe()
+    }
+
+    async fn metrics(&self) -> Option<String> {
+        self.metrics.clone()
+    }
+}
+
+fn sample(five_hour: f64) -> UsageData {
+    UsageData {
+        five_hour: Some(UsageWindow {
+            utilization: five_hour,
+            resets_at: "2026-01-01T12:00:00Z".to_string(),
+            forecast: None,
+        }),
+        seven_day: None,
+        fetched_at: None,
+    }
+}
+
+async fn start(fixture: Fixture) -> (ApiServer, Arc<Fixture>) {
+    let fixture = Arc::new(fixture);
+    let server = ApiServer::start(0, TOKEN.to_string(), fixture.clone()).await.unwrap();
+    (server, fixture)
+}
+
+#[tokio::test]
+async fn requests_need_the_token() {
+    let (server, _) = start(Fixture::default()).await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/usage", server.url());
+
+    assert_eq!(client.get(&url).send().await.unwrap().status(), 401);
+    assert_eq!(client.get(&url).bearer_auth("wrong").send().await.unwrap().status(), 401);
+    assert_eq!(client.get(&url).bearer_auth(TOKEN).send().await.unwrap().status(), 200);
+    let query = format!("{url}?token={TOKEN}");
+    assert_eq!(client.get(&query).send().await.unwrap().status(), 200);
+}
+
+#[tokio::test]
+async fn usage_and_history_are_served_as_json() {
+    let (server, fixture) = start(Fixture {
+        usage: HashMap::from([("Work".to_string(), sample(42.0))]),
+        ..Fixture::default()
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let usage: serde_json::Value = client
+        .get(format!("{}/usage", server.url()))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(usage["Work"]["fiveHour"]["utilization"], 42.0);
+
+    let history = client
+        .get(format!("{}/history", server.url()))
+        .query(&[("profile", "Work"), ("from", "2026-01-01T08:00:00+01:00")])
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(history.status(), 200);
+    let samples: serde_json::Value = history.json().await.unwrap();
+    assert_eq!(samples.as_array().map(Vec::len), Some(1));
+
+    let (profile, from, to) = fixture.queries.lock().unwrap()[0].clone();
+    assert_eq!(profile.as_deref(), Some("Work"));
+    assert_eq!(from.map(|t| t.to_rfc3339()).as_deref(), Some("2026-01-01T07:00:00+00:00"));
+    assert_eq!(to, None);
+
+    let bad = client
+        .get(format!("{}/history?from=yesterday", server.url()))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(bad.status(), 400);
+}
+
+#[tokio::test]
+async fn refresh_must_be_posted() {
+    let (server, fixture) = start(Fixture::default()).await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/refresh", server.url());
+
+    assert_eq!(client.post(&url).bearer_auth(TOKEN).send().await.unwrap().status(), 202);
+    assert_eq!(client.get(&url).bearer_auth(TOKEN).send().await.unwrap().status(), 405);
+    assert_eq!(fixture.refreshes.load(Ordering::SeqCst), 1);
+
+    let missing = client.get(format!("{}/nope", server.url())).bearer_auth(TOKEN).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+}
+
+#[tokio::test]
+async fn metrics_are_served_only_when_turned_on() {
+    let client = reqwest::Client::new();
+
+    let (server, _) = start(Fixture::default()).await;
+    let off = client.get(format!("{}/metrics", server.url())).bearer_auth(TOKEN).send().await.unwrap();
+    assert_eq!(off.status(), 404);
+
+    let (server, _) = start(Fixture {
+        metrics: Some("# EOF\n".to_string()),
+        ..Fixture::default()
+    })
+    .await;
+    let on = client.get(format!("{}/metrics", server.url())).bearer_auth(TOKEN).send().await.unwrap();
+    assert_eq!(on.status(), 200);
+    assert!(on.headers()["content-type"].to_str().unwrap().starts_with("application/openmetrics-text"));
+    assert_eq!(on.text().await.unwrap(), "# EOF\n");
+}
+
+#[tokio::test]
+async fn event_stream_mirrors_usage_updates() {
+    let events = api::usage_events();
+    let (server, _) = start(Fixture {
+        events: Some(events.clone()),
+        ..Fixture::default()
+    })
+    .await;
+
+    let mut stream = reqwest::Client::new()
+        .get(format!("{}/events", server.url()))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(stream.headers()["content-type"], "text/event-stream");
+
+    let mut received = String::new();
+    while !received.contains(": connected") {
+        received.push_str(&String::from_utf8_lossy(&stream.chunk().await.unwrap().unwrap()));
+    }
+
+    events.send(sample(77.0)).unwrap();
+    while !received.contains("event: usage-updated") || !received.ends_with("\n\n") {
+        received.push_str(&String::from_utf8_lossy(&stream.chunk().await.unwrap().unwrap()));
+    }
+    let data = received
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap();
+    let usage: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(usage["fiveHour"]["utilization"], 77.0);
+
+    drop(server);
+    let next = tokio::time::timeout(std::time::Duration::from_secs(5), stream.chunk()).await;
+    assert!(matches!(next, Ok(Ok(None) | Err(_))), "stopping the server ends the stream: {next:?}");
+}
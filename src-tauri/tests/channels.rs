@@ -0,0 +1,176 @@
+use seekers_lib::channels::{self, ChannelConfig, ChannelKind};
+use seekers_lib::mock::WebhookSink;
+use seekers_lib::notify::Alert;
+use std::time::{Duration, Instant};
+
+fn alert() -> Alert {
+    Alert {
+        title: "Claude Session Limit".to_string(),
+        body: "Session usage at 90% (Work)".to_string(),
+        source: None,
+    }
+}
+
+fn channel(kind: ChannelKind, url: String) -> ChannelConfig {
+    ChannelConfig {
+        kind,
+        url,
+        retries: 0,
+        ..ChannelConfig::default()
+    }
+}
+
+async fn deliver(config: &ChannelConfig) -> Result<(), String> {
+    channels::deliver(&reqwest::Client::new(), config, &alert(), Duration::from_millis(10)).await
+}
+
+#[tokio::test]
+async fn each_kind_posts_its_own_payload() {
+    let sink = WebhookSink::start(Vec::new()).await.unwrap();
+
+    deliver(&channel(ChannelKind::Webhook, sink.url("/hook"))).await.unwrap();
+    deliver(&channel(ChannelKind::Slack, sink.url("/slack"))).await.unwrap();
+    deliver(&channel(ChannelKind::Discord, sink.url("/discord"))).await.unwrap();
+    let gotify = ChannelConfig {
+        token: Some("app-token".to_string()),
+        ..channel(ChannelKind::Gotify, sink.url("/message"))
+    };
+    deliver(&gotify).await.unwrap();
+
+    let requests = sink.requests();
+    assert!(requests.iter().all(|r| r.method == "POST"));
+
+    let webhook = requests[0].json();
+    assert_eq!(webhook["title"], "Claude Session Limit");
+    assert_eq!(webhook["body"], "Session usage at 90% (Work)");
+    assert_eq!(webhook["source"], "seekers");
+
+    assert_eq!(requests[1].json()["text"], "*Claude Session Limit*\nSession usage at 90% (Work)");
+    assert_eq!(requests[2].json()["content"], "**Claude Session Limit**\nSession usage at 90% (Work)");
+
+    assert_eq!(requests[3].header("X-Gotify-Key"), Some("app-token"));
+    assert_eq!(requests[3].json()["message"], "Session usage at 90% (Work)");
+}
+
+#[tokio::test]
+async fn ntfy_sends_plain_text_with_the_title_in_the_query() {
+    let sink = WebhookSink::start(Vec::new()).await.unwrap();
+    let ntfy = ChannelConfig {
+        token: Some("tk_secret".to_string()),
+        ..channel(ChannelKind::Ntfy, sink.url("/seekers-alerts"))
+    };
+
+    deliver(&ntfy).await.unwrap();
+
+    let request = &sink.requests()[0];
+    assert_eq!(request.path, "/seekers-alerts?title=Claude+Session+Limit");
+    assert_eq!(request.body, "Session usage at 90% (Work)");
+    assert_eq!(request.header("Authorization"), Some("Bearer tk_secret"));
+}
+
+#[tokio::test]
+async fn templates_fill_in_the_alert() {
+    let sink = WebhookSink::start(Vec::new()).await.unwrap();
+    let config = ChannelConfig {
+        title: "[seekers] {title}".to_string(),
+        message: "{body} — slow down".to_string(),
+        ..channel(ChannelKind::Webhook, sink.url("/hook"))
+    };
+
+    deliver(&config).await.unwrap();
+
+    let payload = sink.requests()[0].json();
+    assert_eq!(payload["title"], "[seekers] Claude Session Limit");
+    assert_eq!(payload["body"], "Session usage at 90% (Work) — slow down");
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    let sink = WebhookSink::start(vec![503, 429]).await.unwrap();
+    let config = ChannelConfig {
+        retries: 2,
+        ..channel(ChannelKind::Slack, sink.url("/slack"))
+    };
+
+    deliver(&config).await.unwrap();
+    assert_eq!(sink.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn rate_limits_wait_for_retry_after_unless_it_is_too_long() {
+    let sink = WebhookSink::start(vec![429]).await.unwrap();
+    sink.set_retry_after(1);
+    let config = ChannelConfig {
+        retries: 1,
+        ..channel(ChannelKind::Ntfy, sink.url("/alerts"))
+    };
+
+    let started = Instant::now();
+    deliver(&config).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(1), "waited {:?}", started.elapsed());
+    assert_eq!(sink.requests().len(), 2);
+
+    let sink = WebhookSink::start(vec![429]).await.unwrap();
+    sink.set_retry_after(3600);
+    let error = deliver(&ChannelConfig { url: sink.url("/alerts"), ..config }).await.unwrap_err();
+    assert!(error.contains("3600s"), "{error}");
+    assert_eq!(sink.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn retries_give_up_and_client_errors_are_not_retried() {
+    let sink = WebhookSink::start(vec![500, 500, 404]).await.unwrap();
+    let config = ChannelConfig {
+        retries: 1,
+        ..channel(ChannelKind::Webhook, sink.url("/hook"))
+    };
+
+    let error = deliver(&config).await.unwrap_err();
+    assert!(error.contains("500"), "{error}");
+    assert_eq!(sink.requests().len(), 2);
+
+    let config = ChannelConfig { retries: 3, ..config };
+    let error = deliver(&config).await.unwrap_err();
+    assert!(error.contains("404"), "{error}");
+    assert_eq!(sink.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn dispatch_skips_disabled_channels_and_reports_failures() {
+    let sink = WebhookSink::start(vec![400]).await.unwrap();
+    let configs = vec![
+        ChannelConfig {
+            name: "Broken".to_string(),
+            ..channel(ChannelKind::Webhook, sink.url("/broken"))
+        },
+        ChannelConfig {
+            enabled: false,
+            ..channel(ChannelKind::Slack, sink.url("/off"))
+        },
+        channel(ChannelKind::Discord, sink.url("/discord")),
+    ];
+
+    let failures = channels::dispatch(&reqwest::Client::new(), &configs, &[alert()]).await;
+
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].channel, "Broken");
+    let paths: Vec<String> = sink.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(paths, ["/broken", "/discord"]);
+}
+
+#[tokio::test]
+async fn dispatch_sends_to_channels_at_the_same_time() {
+    let sink = WebhookSink::start(vec![503, 503]).await.unwrap();
+    let retrying = |path: &str| ChannelConfig {
+        retries: 1,
+        ..channel(ChannelKind::Webhook, sink.url(path))
+    };
+    let configs = vec![retrying("/a"), retrying("/b")];
+
+    let started = Instant::now();
+    let failures = channels::dispatch(&reqwest::Client::new(), &configs, &[alert()]).await;
+
+    assert!(failures.is_empty());
+    assert_eq!(sink.requests().len(), 4);
+    assert!(started.elapsed() < Duration::from_millis(1800), "retries overlapped: {:?}", started.elapsed());
+}
@@ -0,0 +1,23 @@
+use seekers_lib::watch::FileWatch;
+
+#[test]
+fn reports_creation_edits_and_removal() {
+    let dir = std::env::temp_dir().join(format!("seekers-test-watch-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("settings.json");
+
+    let mut watch = FileWatch::new(path.clone());
+    assert!(!watch.changed());
+
+    std::fs::write(&path, "{}").unwrap();
+    assert!(watch.changed(), "created");
+    assert!(!watch.changed(), "reported once");
+
+    std::fs::write(&path, r#"{"refreshInterval": 5}"#).unwrap();
+    assert!(watch.changed(), "edited");
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(watch.changed(), "removed");
+    assert!(!watch.changed());
+}
@@ -0,0 +1,182 @@
+use seekers_lib::credential_store::{self, CredentialStore, FileStore};
+use seekers_lib::credentials::{CredentialsManager, Profile, ProfileSet};
+use seekers_lib::crypto::{self, FileKey};
+use std::path::PathBuf;
+
+const LEGACY: &str = r#"{"orgId":"00000000-0000-0000-0000-000000000000","sessionKey":"sk-ant-sid01-test"}"#;
+
+/// Fresh scratch directory per test, so tests never touch the real config
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("seekers-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn migration_moves_credentials_once() {
+    let dir = scratch_dir("migrate");
+    let legacy = FileStore::new(dir.join("credentials.json"));
+    let target = FileStore::new(dir.join("target.json"));
+    legacy.write(LEGACY).unwrap();
+
+    assert!(credential_store::migrate(&legacy, &target).unwrap());
+    assert_eq!(target.read().unwrap().as_deref(), Some(LEGACY));
+    assert_eq!(legacy.read().unwrap(), None, "plaintext copy is removed");
+
+    legacy.write("{}").unwrap();
+    assert!(!credential_store::migrate(&legacy, &target).unwrap(), "existing target is never overwritten");
+    assert_eq!(target.read().unwrap().as_deref(), Some(LEGACY));
+}
+
+#[test]
+fn manager_reads_legacy_file_through_store() {
+    let dir = scratch_dir("manager");
+    let store = FileStore::new(dir.join("credentials.json"));
+    store.write(LEGACY).unwrap();
+    let manager = CredentialsManager::with_store(Box::new(store));
+
+    let creds = manager.load().unwrap();
+    assert_eq!(creds.session_key, "sk-ant-sid01-test");
+
+    manager.save(&creds.org_id, "sk-ant-sid01-new").unwrap();
+    let set = manager.load_profiles().unwrap();
+    assert_eq!(set.profiles.len(), 1);
+    assert_eq!(set.profiles[0].session_key, "sk-ant-sid01-new");
+    assert_eq!(manager.store_name(), "file");
+}
+
+#[test]
+fn profiles_are_read_once_until_reloaded() {
+    let dir = scratch_dir("cache");
+    let path = dir.join("credentials.json");
+    FileStore::new(path.clone()).write(LEGACY).unwrap();
+    let manager = CredentialsManager::with_store(Box::new(FileStore::new(path.clone())));
+    assert_eq!(manager.load_profiles().unwrap().profiles[0].session_key, "sk-ant-sid01-test");
+
+    // An edit made behind the manager's back shows up only after a reload
+    FileStore::new(path).write(&LEGACY.replace("sk-ant-sid01-test", "sk-ant-sid01-edited")).unwrap();
+    assert_eq!(manager.load_profiles().unwrap().profiles[0].session_key, "sk-ant-sid01-test");
+    assert_eq!(manager.reload_profiles().unwrap().profiles[0].session_key, "sk-ant-sid01-edited");
+
+    manager.save(&"1".repeat(36), "sk-ant-sid01-saved").unwrap();
+    assert_eq!(manager.load_profiles().unwrap().profiles[0].session_key, "sk-ant-sid01-saved");
+}
+
+#[test]
+fn encrypted_file_round_trips_and_detects_format() {
+    let dir = scratch_dir("encrypted");
+    let path = dir.join("credentials.json");
+    let key = FileKey::Passphrase("correct horse battery staple".to_string());
+
+    // A plaintext file is still readable and gets upgraded in place
+    FileStore::new(path.clone()).write(LEGACY).unwrap();
+    let store = FileStore::encrypted(path.clone(), key);
+    assert!(store.encrypt_existing().unwrap());
+    assert!(!store.encrypt_existing().unwrap());
+
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert!(crypto::is_sealed(&raw));
+    assert!(!raw.contains("sk-ant-sid01-test"));
+    assert_eq!(store.read().unwrap().as_deref(), Some(LEGACY));
+
+    let wrong = FileStore::encrypted(path.clone(), FileKey::Passphrase("wrong".to_string()));
+    assert!(wrong.read().is_err());
+    assert!(FileStore::new(path).read().is_err(), "encrypted file needs a key");
+}
+
+#[test]
+fn key_file_is_created_once_and_reused() {
+    let dir = scratch_dir("keyfile");
+    let key = FileKey::KeyFile(dir.join("credentials.key"));
+    let store = FileStore::encrypted(dir.join("credentials.json"), key.clone());
+
+    let reopened = FileStore::encrypted(dir.join("credentials.json"), key);
+    store.write(LEGACY).unwrap();
+    assert_eq!(reopened.read().unwrap().as_deref(), Some(LEGACY));
+
+    std::fs::remove_file(dir.join("credentials.key")).unwrap();
+    assert!(store.read().is_err(), "a lost key file is not silently regenerated");
+}
+
+#[test]
+fn renaming_onto_another_profile_is_refused() {
+    let profile = |name: &str, key: &str| Profile {
+        name: name.to_string(),
+        org_id: "00000000-0000-0000-0000-000000000000".to_string(),
+        session_key: key.to_string(),
+        expired: false,
+    };
+    let mut set = ProfileSet::default();
+    set.upsert(profile("Personal", "sk-ant-sid01-personal"), None).unwrap();
+    set.upsert(profile("Work", "sk-ant-sid01-work"), None).unwrap();
+
+    let error = set.upsert(profile("Work", "sk-ant-sid01-personal"), Some("Personal")).unwrap_err();
+    assert_eq!(error, "A profile named \"Work\" already exists");
+    assert_eq!(set.get("Work").unwrap().session_key, "sk-ant-sid01-work");
+    assert!(set.get("Personal").is_some());
+
+    set.upsert(profile("Home", "sk-ant-sid01-personal"), Some("Personal")).unwrap();
+    let names: Vec<_> = set.profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Home", "Work"]);
+    assert_eq!(set.active.as_deref(), Some("Home"), "the active profile follows its rename");
+}
+
+/// A store that takes in a separate file, like the keyring does with `credentials.json`
+struct Importing {
+    store: FileStore,
+    file: FileStore,
+}
+
+impl CredentialStore for Importing {
+    fn name(&self) -> &'static str {
+        "importing"
+    }
+
+    fn read(&self) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        self.store.read()
+    }
+
+    fn write(&self, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.store.write(contents)
+    }
+
+    fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.store.clear()
+    }
+
+    fn watched_path(&self) -> Option<&std::path::Path> {
+        self.file.path()
+    }
+
+    fn pending_import(&self) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        self.file.read()
+    }
+
+    fn import_watched(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        credential_store::import(&self.file, &self.store)
+    }
+}
+
+#[test]
+fn edited_file_is_imported_on_reload_only_when_valid() {
+    let dir = scratch_dir("import");
+    let file_path = dir.join("credentials.json");
+    let store = FileStore::new(dir.join("keyring.json"));
+    store.write(LEGACY).unwrap();
+    let manager = CredentialsManager::with_store(Box::new(Importing {
+        store,
+        file: FileStore::new(file_path.clone()),
+    }));
+    assert_eq!(manager.watched_path(), Some(file_path.clone()));
+
+    std::fs::write(&file_path, "{ not json").unwrap();
+    assert!(manager.reload_profiles().is_err());
+    assert!(file_path.exists(), "an invalid file is left alone");
+    assert_eq!(manager.load_profiles().unwrap().profiles[0].session_key, "sk-ant-sid01-test");
+
+    std::fs::write(&file_path, LEGACY.replace("sk-ant-sid01-test", "sk-ant-sid01-edited")).unwrap();
+    let set = manager.reload_profiles().unwrap();
+    assert_eq!(set.profiles[0].session_key, "sk-ant-sid01-edited");
+    assert!(!file_path.exists(), "moved into the store");
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use seekers_lib::forecast::project;
+use seekers_lib::history::{UsageSample, WindowSample};
+use seekers_lib::WindowKind;
+
+const RESETS_AT: &str = "2026-03-01T20:00:00Z";
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap()
+}
+
+fn sample(minutes_ago: i64, utilization: f64, resets_at: &str) -> UsageSample {
+    UsageSample {
+        timestamp: now() - Duration::minutes(minutes_ago),
+        profile: "Default".to_string(),
+        five_hour: Some(WindowSample {
+            utilization,
+            resets_at: resets_at.to_string(),
+        }),
+        seven_day: None,
+    }
+}
+
+#[test]
+fn rate_is_measured_over_the_lookback_only() {
+    let samples = [
+        sample(180, 0.0, RESETS_AT),
+        sample(50, 10.0, RESETS_AT),
+        sample(0, 20.0, RESETS_AT),
+    ];
+
+    let forecast = project(WindowKind::FiveHour, &samples).unwrap();
+
+    assert!((forecast.rate_per_hour - 12.0).abs() < 1e-9, "{}", forecast.rate_per_hour);
+    assert_eq!(forecast.limit_at, Some(now() + Duration::minutes(400)));
+}
+
+#[test]
+fn too_short_a_span_has_no_forecast() {
+    let samples = [sample(3, 10.0, RESETS_AT), sample(0, 20.0, RESETS_AT)];
+
+    assert!(project(WindowKind::FiveHour, &samples).is_none());
+    assert!(project(WindowKind::FiveHour, &samples[1..]).is_none(), "a single sample measures nothing");
+}
+
+#[test]
+fn samples_from_an_earlier_cycle_are_ignored() {
+    let samples = [
+        sample(40, 90.0, "2026-03-01T09:45:00Z"),
+        sample(30, 2.0, RESETS_AT),
+        sample(0, 8.0, RESETS_AT),
+    ];
+
+    let forecast = project(WindowKind::FiveHour, &samples).unwrap();
+    assert!((forecast.rate_per_hour - 12.0).abs() < 1e-9, "{}", forecast.rate_per_hour);
+
+    let just_reset = [sample(30, 90.0, "2026-03-01T09:45:00Z"), sample(0, 8.0, RESETS_AT)];
+    assert!(project(WindowKind::FiveHour, &just_reset).is_none());
+}
+
+#[test]
+fn limit_after_the_reset_is_not_projected() {
+    let samples = [sample(60, 10.0, RESETS_AT), sample(0, 15.0, RESETS_AT)];
+
+    let forecast = project(WindowKind::FiveHour, &samples).unwrap();
+
+    assert!((forecast.rate_per_hour - 5.0).abs() < 1e-9);
+    assert_eq!(forecast.limit_at, None, "17 hours to the limit, the window resets in 10");
+}
+
+#[test]
+fn flat_or_falling_usage_never_reaches_the_limit() {
+    let flat = [sample(60, 40.0, RESETS_AT), sample(0, 40.0, RESETS_AT)];
+    let falling = [sample(60, 40.0, RESETS_AT), sample(0, 35.0, RESETS_AT)];
+
+    let flat = project(WindowKind::FiveHour, &flat).unwrap();
+    let falling = project(WindowKind::FiveHour, &falling).unwrap();
+
+    assert_eq!(flat.limit_at, None);
+    assert_eq!(falling.limit_at, None);
+    assert!(falling.rate_per_hour < 0.0);
+}
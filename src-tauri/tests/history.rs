@@ -0,0 +1,79 @@
+use chrono::{Duration, TimeZone, Utc};
+use seekers_lib::history::{HistoryStore, UsageSample};
+use seekers_lib::{UsageData, UsageWindow, WindowKind};
+use std::path::PathBuf;
+
+fn store(name: &str) -> (HistoryStore, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("seekers-test-history-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("history.jsonl");
+    (HistoryStore::at(path.clone()), path)
+}
+
+fn usage(five_hour: f64, seven_day: Option<f64>) -> UsageData {
+    let window = |utilization| UsageWindow {
+        utilization,
+        resets_at: "2026-03-01T12:00:00Z".to_string(),
+        forecast: None,
+    };
+    UsageData {
+        five_hour: Some(window(five_hour)),
+        seven_day: seven_day.map(window),
+        fetched_at: None,
+    }
+}
+
+#[test]
+fn samples_are_queried_by_profile_range_and_window() {
+    let (store, _) = store("query");
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+    // Appended out of order, as two profiles refreshing concurrently can
+    store.append(&UsageSample::from_usage("Work", &usage(20.0, Some(5.0)), start + Duration::hours(1))).unwrap();
+    store.append(&UsageSample::from_usage("Work", &usage(10.0, None), start)).unwrap();
+    store.append(&UsageSample::from_usage("Home", &usage(70.0, Some(30.0)), start)).unwrap();
+    store.append(&UsageSample::from_usage("Work", &usage(30.0, Some(6.0)), start + Duration::hours(2))).unwrap();
+
+    let work = store.query(Some("Work"), None, None).unwrap();
+    let values: Vec<_> = work.iter().map(|s| s.five_hour.as_ref().unwrap().utilization).collect();
+    assert_eq!(values, [10.0, 20.0, 30.0], "oldest first");
+
+    let ranged = store
+        .query(None, Some(start + Duration::minutes(30)), Some(start + Duration::hours(1)))
+        .unwrap();
+    assert_eq!(ranged.len(), 1);
+    assert_eq!(ranged[0].timestamp, start + Duration::hours(1));
+
+    let weekly = store.query_window(Some("Work"), WindowKind::SevenDay, None, None).unwrap();
+    let values: Vec<_> = weekly.iter().map(|p| p.utilization).collect();
+    assert_eq!(values, [5.0, 6.0], "samples without the window are left out");
+
+    assert_eq!(store.rename_profile("Work", "Office").unwrap(), 3);
+    assert!(store.query(Some("Work"), None, None).unwrap().is_empty());
+    assert_eq!(store.query(Some("Office"), None, None).unwrap().len(), 3);
+    assert_eq!(store.query(Some("Home"), None, None).unwrap().len(), 1);
+
+    assert_eq!(store.remove_profile("Office").unwrap(), 3);
+    assert!(store.query(Some("Office"), None, None).unwrap().is_empty());
+    assert_eq!(store.query(None, None, None).unwrap().len(), 1, "other profiles are kept");
+}
+
+#[test]
+fn old_and_unreadable_lines_are_pruned() {
+    let (store, path) = store("prune");
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+    let line = |days: i64, pct: f64| {
+        serde_json::to_string(&UsageSample::from_usage("Work", &usage(pct, None), now - Duration::days(days))).unwrap()
+    };
+    std::fs::write(&path, format!("{}\nnot json\n{}\n", line(200, 10.0), line(1, 20.0))).unwrap();
+
+    assert_eq!(store.prune(now - Duration::days(90)).unwrap(), 2);
+    assert_eq!(store.query(None, None, None).unwrap().len(), 1);
+    assert_eq!(store.prune(now - Duration::days(90)).unwrap(), 0, "nothing left to drop");
+
+    // Appending prunes past the retention period on its own
+    store.append(&UsageSample::from_usage("Work", &usage(30.0, None), now + Duration::days(100))).unwrap();
+    let left = store.query(None, None, None).unwrap();
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].timestamp, now + Duration::days(100));
+}
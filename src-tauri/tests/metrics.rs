@@ -0,0 +1,68 @@
+use chrono::{Duration, TimeZone, Utc};
+use seekers_lib::error::FetchError;
+use seekers_lib::metrics::Metrics;
+use seekers_lib::{UsageData, UsageWindow};
+use std::collections::HashMap;
+
+fn usage(five_hour: f64, seven_day: f64, resets_at: &str) -> UsageData {
+    let window = |utilization| UsageWindow {
+        utilization,
+        resets_at: resets_at.to_string(),
+        forecast: None,
+    };
+    UsageData {
+        five_hour: Some(window(five_hour)),
+        seven_day: Some(window(seven_day)),
+        fetched_at: None,
+    }
+}
+
+#[test]
+fn usage_is_exported_per_window_and_profile() {
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+    let metrics = Metrics::new();
+    let data = usage(42.5, 18.0, "2026-01-01T12:00:00Z");
+    let shared = usage(7.0, 3.0, "2026-01-01T12:00:00Z");
+    metrics.record_fetch("Work", "org-1", &Ok(data.clone()), std::time::Duration::from_millis(300));
+    metrics.record_fetch("Second key", "org-1", &Ok(shared.clone()), std::time::Duration::from_millis(300));
+
+    let profiles = HashMap::from([
+        ("Work".to_string(), data),
+        // Same org under another session key is its own series
+        ("Second key".to_string(), shared),
+        // Never fetched in this run, so its org is unknown
+        ("Cached".to_string(), usage(99.0, 99.0, "2026-01-01T12:00:00Z")),
+    ]);
+    let text = metrics.render(&profiles, now);
+
+    assert!(text.contains("# TYPE seekers_utilization gauge\n"));
+    assert!(text.contains("seekers_utilization{window=\"five_hour\",org=\"org-1\",profile=\"Work\"} 42.5\n"));
+    assert!(text.contains("seekers_utilization{window=\"seven_day\",org=\"org-1\",profile=\"Work\"} 18\n"));
+    assert!(text.contains("seekers_utilization{window=\"five_hour\",org=\"org-1\",profile=\"Second key\"} 7\n"));
+    assert!(text.contains("seekers_seconds_until_reset{window=\"five_hour\",org=\"org-1\",profile=\"Work\"} 7200\n"));
+    assert!(!text.contains("99"));
+    assert!(text.ends_with("# EOF\n"));
+}
+
+#[test]
+fn fetches_are_counted_and_timed() {
+    let metrics = Metrics::new();
+    let ok = Ok(usage(10.0, 5.0, "2026-01-01T12:00:00Z"));
+    metrics.record_fetch("Work", "org-1", &ok, std::time::Duration::from_millis(80));
+    metrics.record_fetch("Work", "org-1", &ok, std::time::Duration::from_millis(700));
+    metrics.record_fetch("Work", "org-1", &Err(FetchError::Offline), std::time::Duration::from_secs(30));
+    metrics.record_fetch("Work", "org-1", &Err(FetchError::Server { status: 502 }), std::time::Duration::from_secs(2));
+
+    let text = metrics.render(&HashMap::new(), Utc::now() + Duration::hours(1));
+
+    assert!(text.contains("seekers_fetch_success_total{org=\"org-1\"} 2\n"));
+    assert!(text.contains("seekers_fetch_failure_total{org=\"org-1\",reason=\"offline\"} 1\n"));
+    assert!(text.contains("seekers_fetch_failure_total{org=\"org-1\",reason=\"server\"} 1\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_bucket{org=\"org-1\",le=\"0.1\"} 1\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_bucket{org=\"org-1\",le=\"1.0\"} 2\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_bucket{org=\"org-1\",le=\"2.5\"} 3\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_bucket{org=\"org-1\",le=\"10.0\"} 3\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_bucket{org=\"org-1\",le=\"+Inf\"} 4\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_count{org=\"org-1\"} 4\n"));
+    assert!(text.contains("seekers_fetch_duration_seconds_sum{org=\"org-1\"} 32.78\n"));
+}
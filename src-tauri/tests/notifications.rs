@@ -0,0 +1,299 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use seekers_lib::actions;
+use seekers_lib::forecast::Forecast;
+use seekers_lib::notify::{
+    self, Alert, NotificationRule, NotifyState, QuietHours, QuietMode, QuietPeriod, QuietSchedule, RuleCondition,
+};
+use seekers_lib::settings::AppSettings;
+use seekers_lib::{UsageData, UsageWindow, WindowKind};
+
+const PROFILE: &str = "Default";
+
+fn usage(utilization: f64, resets_at: DateTime<Utc>, forecast: Option<Forecast>) -> UsageData {
+    UsageData {
+        five_hour: Some(UsageWindow {
+            utilization,
+            resets_at: resets_at.to_rfc3339(),
+            forecast,
+        }),
+        seven_day: None,
+        fetched_at: None,
+    }
+}
+
+fn with_rules(rules: Vec<NotificationRule>) -> AppSettings {
+    AppSettings {
+        notification_rules: rules,
+        ..AppSettings::default()
+    }
+}
+
+fn rule(id: &str, condition: RuleCondition) -> NotificationRule {
+    NotificationRule {
+        id: id.to_string(),
+        condition,
+        ..NotificationRule::crosses(id, WindowKind::FiveHour, 0)
+    }
+}
+
+fn bodies(alerts: &[notify::Alert]) -> Vec<&str> {
+    alerts.iter().map(|a| a.body.as_str()).collect()
+}
+
+#[test]
+fn forecast_and_rate_rules_fire_on_their_conditions() {
+    let now = Utc::now();
+    let resets_at = now + Duration::hours(3);
+    let settings = with_rules(vec![
+        rule("soon", RuleCondition::ProjectedLimit { minutes: 60 }),
+        NotificationRule {
+            message: "{profile}: {rate}%/h on the {window} window".to_string(),
+            ..rule("fast", RuleCondition::RateAbove { percent_per_hour: 25.0 })
+        },
+    ]);
+    let mut state = NotifyState::default();
+
+    let calm = Forecast { rate_per_hour: 10.0, limit_at: None };
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(40.0, resets_at, Some(calm)), &settings, now);
+    assert!(alerts.is_empty());
+
+    let burning = Forecast { rate_per_hour: 30.0, limit_at: Some(now + Duration::minutes(45)) };
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(70.0, resets_at, Some(burning)), &settings, now);
+    assert_eq!(bodies(&alerts), ["Session limit in 45m at this pace", "Default: 30%/h on the Session window"]);
+    let titles: Vec<_> = alerts.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(titles, ["Claude Session Limit Ahead", "Claude Session Usage Climbing"]);
+}
+
+#[test]
+fn escalating_levels_fire_once_per_reset_cycle() {
+    let now = Utc::now();
+    let cycle = now + Duration::hours(2);
+    let settings = with_rules(vec![NotificationRule::escalating("session", WindowKind::FiveHour, &[50, 75, 90, 100])]);
+    let mut state = NotifyState::default();
+    let mut sample = |pct: f64, resets_at: DateTime<Utc>| {
+        let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(pct, resets_at, None), &settings, now);
+        alerts.into_iter().map(|a| a.body).collect::<Vec<_>>()
+    };
+
+    assert!(sample(40.0, cycle).is_empty());
+    assert_eq!(sample(55.0, cycle), ["Session usage at 55%"]);
+    assert!(sample(60.0, cycle).is_empty());
+    assert!(sample(20.0, cycle).is_empty());
+    assert!(sample(55.0, cycle).is_empty(), "only a new reset time re-arms a level");
+    assert_eq!(sample(92.0, cycle), ["Session usage at 92%"], "75 and 90 together alert once");
+    assert!(sample(95.0, cycle + Duration::milliseconds(400)).is_empty(), "jitter is the same cycle");
+    assert_eq!(sample(100.0, cycle), ["Session usage at 100%"]);
+
+    // The next cycle re-arms every level, even though usage never dipped in between
+    let next = cycle + Duration::hours(5);
+    assert_eq!(sample(100.0, next), ["Session usage at 100%"]);
+    assert!(sample(100.0, next).is_empty());
+}
+
+#[test]
+fn a_window_without_a_reset_time_reaches_nothing() {
+    let now = Utc::now();
+    let settings = with_rules(vec![NotificationRule::crosses("session", WindowKind::FiveHour, 50)]);
+    let mut state = NotifyState::default();
+    let mut unstarted = usage(60.0, now, None);
+    if let Some(window) = unstarted.five_hour.as_mut() {
+        window.resets_at = String::new();
+    }
+
+    assert!(notify::evaluate(&mut state, PROFILE, None, &unstarted, &settings, now).is_empty());
+    let started = usage(60.0, now + Duration::hours(5), None);
+    assert_eq!(notify::evaluate(&mut state, PROFILE, None, &started, &settings, now).len(), 1);
+}
+
+#[test]
+fn reset_rule_fires_once_when_the_window_rolls_over() {
+    let start = Utc::now();
+    let first_cycle = start + Duration::minutes(30);
+    let settings = with_rules(vec![rule("reset", RuleCondition::Reset)]);
+    let mut state = NotifyState::default();
+
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(95.0, first_cycle, None), &settings, start);
+    assert!(alerts.is_empty(), "the first sample has nothing to compare with");
+
+    let later = first_cycle + Duration::minutes(1);
+    let next_cycle = later + Duration::hours(5);
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(0.0, next_cycle, None), &settings, later);
+    assert_eq!(bodies(&alerts), ["Your Claude Session limit has reset"]);
+    assert_eq!(alerts[0].title, "Claude Session Reset");
+
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(3.0, next_cycle, None), &settings, later);
+    assert!(alerts.is_empty());
+}
+
+#[test]
+fn a_reset_needs_the_old_time_passed_and_a_new_one() {
+    let now = Utc::now();
+    let ended = usage(97.0, now - Duration::minutes(1), None);
+    let fresh = usage(0.0, now + Duration::hours(5), None);
+
+    assert_eq!(notify::window_resets(&ended, &fresh, now), [WindowKind::FiveHour]);
+
+    let used_again = usage(40.0, now + Duration::hours(5), None);
+    assert_eq!(notify::window_resets(&ended, &used_again, now), [WindowKind::FiveHour], "usage doesn't matter");
+
+    let stale = usage(0.0, now - Duration::minutes(1), None);
+    assert!(notify::window_resets(&ended, &stale, now).is_empty(), "reset time didn't move");
+
+    let not_yet = usage(97.0, now + Duration::minutes(10), None);
+    assert!(notify::window_resets(&not_yet, &fresh, now).is_empty(), "old reset time still ahead");
+}
+
+#[test]
+fn cooldown_and_quiet_hours_hold_alerts_back() {
+    let now = Utc::now();
+    let resets_at = now + Duration::hours(3);
+    let mut settings = with_rules(vec![NotificationRule {
+        cooldown_minutes: 60,
+        ..rule("fast", RuleCondition::RateAbove { percent_per_hour: 25.0 })
+    }]);
+    let mut state = NotifyState::default();
+
+    let pace = |rate_per_hour| usage(40.0, resets_at, Some(Forecast { rate_per_hour, limit_at: None }));
+    let (high, low) = (pace(30.0), pace(10.0));
+    assert_eq!(notify::evaluate(&mut state, PROFILE, None, &high, &settings, now).len(), 1);
+    notify::evaluate(&mut state, PROFILE, None, &low, &settings, now);
+
+    let soon = now + Duration::minutes(10);
+    assert!(notify::evaluate(&mut state, PROFILE, None, &high, &settings, soon).is_empty(), "cooling down");
+    let later = now + Duration::minutes(61);
+    assert_eq!(
+        notify::evaluate(&mut state, PROFILE, None, &high, &settings, later).len(),
+        1,
+        "held back, not lost, while cooling down"
+    );
+    notify::evaluate(&mut state, PROFILE, None, &low, &settings, later);
+
+    let night = Local
+        .from_local_datetime(&(Local::now() + Duration::days(1)).date_naive().and_hms_opt(2, 0, 0).unwrap())
+        .earliest()
+        .unwrap()
+        .with_timezone(&Utc);
+    settings.notification_rules[0].quiet_hours = Some(QuietHours {
+        start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+    });
+    assert!(notify::evaluate(&mut state, PROFILE, None, &high, &settings, night).is_empty(), "quiet at 2am");
+}
+
+fn local(date: NaiveDate, hour: u32) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+        .earliest()
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn quiet_periods_run_past_midnight_into_the_next_day() {
+    let friday_night = QuietPeriod {
+        days: vec![Weekday::Fri],
+        start: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+    };
+    // 2026-01-02 is a Friday
+    let friday = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+    let at = |date: NaiveDate, hour| date.and_hms_opt(hour, 0, 0).unwrap();
+
+    assert!(!friday_night.contains(at(friday, 22)));
+    assert!(friday_night.contains(at(friday, 23)));
+    assert!(friday_night.contains(at(friday.succ_opt().unwrap(), 8)));
+    assert!(!friday_night.contains(at(friday.succ_opt().unwrap(), 23)), "Saturday isn't listed");
+    assert!(!friday_night.contains(at(friday.pred_opt().unwrap(), 23)));
+}
+
+#[test]
+fn alerts_in_quiet_hours_are_digested_or_dropped() {
+    let friday = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+    let mut schedule = QuietSchedule {
+        enabled: true,
+        ..QuietSchedule::default()
+    };
+    let alert = |body: &str| Alert {
+        title: "Claude Session Limit".to_string(),
+        body: body.to_string(),
+        source: None,
+    };
+    let mut state = NotifyState::default();
+
+    let noon = local(friday, 12);
+    assert_eq!(state.hold(vec![alert("Session usage at 55%")], &schedule, noon).len(), 1);
+
+    let night = local(friday, 23);
+    assert!(state.hold(vec![alert("Session usage at 75%")], &schedule, night).is_empty());
+    assert!(state.hold(vec![alert("Session usage at 92%")], &schedule, night).is_empty());
+    assert_eq!(state.release(&schedule, night), None, "still quiet");
+
+    let morning = local(friday.succ_opt().unwrap(), 8);
+    let digest = state.release(&schedule, morning).unwrap();
+    assert_eq!(digest.title, "2 Claude Alerts During Quiet Hours");
+    assert_eq!(digest.body, "Session usage at 75%\nSession usage at 92%");
+    assert_eq!(state.release(&schedule, morning), None, "sent once");
+
+    schedule.mode = QuietMode::Drop;
+    assert!(state.hold(vec![alert("Session usage at 100%")], &schedule, night).is_empty());
+    assert_eq!(state.release(&schedule, morning), None);
+}
+
+#[test]
+fn dedup_state_survives_a_restart() {
+    let now = Utc::now();
+    let settings = AppSettings::default();
+    let high = usage(85.0, now + Duration::hours(3), None);
+    let mut state = NotifyState::default();
+    assert_eq!(notify::evaluate(&mut state, PROFILE, None, &high, &settings, now).len(), 1);
+
+    let saved = serde_json::to_string(&state).unwrap();
+    let mut restarted: NotifyState = serde_json::from_str(&saved).unwrap();
+
+    assert!(notify::evaluate(&mut restarted, PROFILE, None, &high, &settings, now).is_empty());
+
+    restarted.rename_profile(PROFILE, "Work");
+    assert!(notify::evaluate(&mut restarted, "Work", None, &high, &settings, now).is_empty(), "kept across a rename");
+
+    restarted.remove_profile("Work");
+    let alerts = notify::evaluate(&mut restarted, "Work", None, &high, &settings, now);
+    assert_eq!(alerts.len(), 1, "a deleted profile's name starts fresh");
+}
+
+#[test]
+fn snoozed_windows_stay_silent_until_the_snooze_expires() {
+    let now = Utc::now();
+    let resets_at = now + Duration::hours(3);
+    let settings = with_rules(vec![NotificationRule::escalating("session", WindowKind::FiveHour, &[50, 90, 100])]);
+    let mut state = NotifyState::default();
+
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(55.0, resets_at, None), &settings, now);
+    let snooze = actions::for_alert(&alerts[0])
+        .into_iter()
+        .find(|(_, label)| *label == "Snooze 1h")
+        .map(|(id, _)| id)
+        .unwrap();
+    let source = actions::parse_snooze(&snooze).unwrap();
+    assert_eq!(Some(&source), alerts[0].source.as_ref());
+    state.snooze(&source.profile, source.window, now + Duration::hours(1));
+
+    let saved = serde_json::to_string(&state).unwrap();
+    let mut state: NotifyState = serde_json::from_str(&saved).unwrap();
+
+    let snoozed = now + Duration::minutes(30);
+    assert!(notify::evaluate(&mut state, PROFILE, None, &usage(92.0, resets_at, None), &settings, snoozed).is_empty());
+
+    let awake = now + Duration::minutes(90);
+    let alerts = notify::evaluate(&mut state, PROFILE, None, &usage(100.0, resets_at, None), &settings, awake);
+    assert_eq!(bodies(&alerts), ["Session usage at 100%"]);
+}
+
+#[test]
+fn only_a_few_alerts_wait_for_a_click_at_once() {
+    let slots: Vec<_> = std::iter::from_fn(actions::reserve).take(10).collect();
+    assert_eq!(slots.len(), 3);
+    assert!(actions::reserve().is_none());
+
+    drop(slots);
+    assert!(actions::reserve().is_some(), "answered alerts free their slot");
+}
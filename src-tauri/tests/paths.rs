@@ -0,0 +1,85 @@
+use seekers_lib::paths::{config_dir_arg, legacy_dir, migrate_legacy, Dirs};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+    let vars: HashMap<String, OsString> = vars.iter().map(|(k, v)| ((*k).to_string(), (*v).into())).collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn defaults_follow_home() {
+    let dirs = Dirs::resolve(None, env(&[("HOME", "/home/ada")]));
+
+    assert_eq!(dirs.config, PathBuf::from("/home/ada/.config/seekers"));
+    assert_eq!(dirs.data, PathBuf::from("/home/ada/.local/share/seekers"));
+    assert!(!dirs.isolated);
+}
+
+#[test]
+fn xdg_homes_are_honored_and_relative_ones_ignored() {
+    let dirs = Dirs::resolve(
+        None,
+        env(&[
+            ("HOME", "/home/ada"),
+            ("XDG_CONFIG_HOME", "/etc/ada"),
+            ("XDG_DATA_HOME", "relative/data"),
+        ]),
+    );
+
+    assert_eq!(dirs.config, PathBuf::from("/etc/ada/seekers"));
+    assert_eq!(dirs.data, PathBuf::from("/home/ada/.local/share/seekers"));
+}
+
+#[test]
+fn explicit_config_dir_isolates_the_instance() {
+    let vars = env(&[("HOME", "/home/ada"), ("SEEKERS_CONFIG_DIR", "/tmp/a"), ("XDG_DATA_HOME", "/data")]);
+
+    let from_env = Dirs::resolve(None, &vars);
+    assert_eq!(from_env.config, PathBuf::from("/tmp/a"));
+    assert_eq!(from_env.data, PathBuf::from("/tmp/a"));
+    assert!(from_env.isolated);
+
+    let from_flag = Dirs::resolve(Some(PathBuf::from("/tmp/b")), &vars);
+    assert_eq!(from_flag.config, PathBuf::from("/tmp/b"), "the flag wins over the environment");
+}
+
+#[test]
+fn config_dir_flag_is_found_in_either_form() {
+    let args = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    assert_eq!(config_dir_arg(args(&["--config-dir", "/tmp/a"])), Some(PathBuf::from("/tmp/a")));
+    assert_eq!(config_dir_arg(args(&["-v", "--config-dir=/tmp/b"])), Some(PathBuf::from("/tmp/b")));
+    assert_eq!(config_dir_arg(args(&["--config-dir"])), None);
+}
+
+#[test]
+fn legacy_files_move_to_the_xdg_directories_once() {
+    let home = std::env::temp_dir().join(format!("seekers-test-legacy-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    let xdg_config = home.join("xdg-config");
+    let pairs = [("HOME", home.to_str().unwrap()), ("XDG_CONFIG_HOME", xdg_config.to_str().unwrap())];
+    let vars = env(&pairs);
+    let legacy = legacy_dir(&vars);
+    assert_eq!(legacy, home.join(".config/seekers"));
+
+    std::fs::create_dir_all(&legacy).unwrap();
+    std::fs::write(legacy.join("settings.json"), "{}").unwrap();
+    std::fs::write(legacy.join("history.jsonl"), "").unwrap();
+    let dirs = Dirs::resolve(None, &vars);
+    std::fs::create_dir_all(&dirs.config).unwrap();
+    std::fs::write(dirs.config.join("credentials.json"), "new").unwrap();
+    std::fs::write(legacy.join("credentials.json"), "old").unwrap();
+
+    let moved = migrate_legacy(&legacy, &dirs);
+
+    assert_eq!(moved, [dirs.config.join("settings.json"), dirs.data.join("history.jsonl")]);
+    assert!(!legacy.join("settings.json").exists());
+    assert_eq!(std::fs::read_to_string(dirs.config.join("credentials.json")).unwrap(), "new", "never replaced");
+    assert!(migrate_legacy(&legacy, &dirs).is_empty());
+
+    let isolated = Dirs::resolve(Some(home.join("instance")), &vars);
+    std::fs::write(legacy.join("settings.json"), "{}").unwrap();
+    assert!(migrate_legacy(&legacy, &isolated).is_empty());
+}
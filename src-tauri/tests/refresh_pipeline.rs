@@ -0,0 +1,344 @@
+use seekers_lib::claude;
+use seekers_lib::credentials::{Profile, ProfileSet};
+use seekers_lib::display::{format_tray_title, format_updated, tray_title};
+use seekers_lib::error::FetchError;
+use seekers_lib::history::HistoryStore;
+use seekers_lib::metrics::Metrics;
+use seekers_lib::mock::{MockResponse, MockServer};
+use seekers_lib::notify::{self, NotifyState};
+use seekers_lib::paths;
+use seekers_lib::refresh::Pipeline;
+use seekers_lib::settings::{AppSettings, MenuBarDisplay, StaleMarker};
+use seekers_lib::UsageData;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+const ORG_ID: &str = "00000000-0000-0000-0000-000000000000";
+const SESSION_KEY: &str = "sk-ant-sid01-test";
+const PROFILE: &str = "Default";
+
+async fn fetch(server: &MockServer) -> Result<UsageData, FetchError> {
+    let client = reqwest::Client::new();
+    claude::fetch_usage(&server.base_url(), ORG_ID, SESSION_KEY, &client).await
+}
+
+fn profile() -> Profile {
+    Profile {
+        name: PROFILE.to_string(),
+        org_id: ORG_ID.to_string(),
+        session_key: SESSION_KEY.to_string(),
+        expired: false,
+    }
+}
+
+/// What the app keeps between refreshes, pointed at `server` with its files in a scratch directory
+struct App {
+    client: reqwest::Client,
+    settings: Mutex<AppSettings>,
+    history: HistoryStore,
+    metrics: Metrics,
+    usage: Mutex<HashMap<String, UsageData>>,
+    errors: Mutex<HashMap<String, FetchError>>,
+    notify_state: Mutex<NotifyState>,
+}
+
+impl App {
+    fn new(name: &str, server: &MockServer, settings: AppSettings) -> Self {
+        let dir = std::env::temp_dir().join(format!("seekers-test-refresh-{}", std::process::id()));
+        paths::init(Some(dir.clone()));
+        let history = dir.join(format!("history-{name}.jsonl"));
+        let _ = std::fs::remove_file(&history);
+
+        Self {
+            client: reqwest::Client::new(),
+            settings: Mutex::new(AppSettings {
+                api_base_url: Some(server.base_url()),
+                ..settings
+            }),
+            history: HistoryStore::at(history),
+            metrics: Metrics::new(),
+            usage: Mutex::new(HashMap::new()),
+            errors: Mutex::new(HashMap::new()),
+            notify_state: Mutex::new(NotifyState::default()),
+        }
+    }
+
+    fn pipeline(&self) -> Pipeline<'_> {
+        Pipeline {
+            client: &self.client,
+            settings: &self.settings,
+            history: &self.history,
+            metrics: &self.metrics,
+            usage: &self.usage,
+            errors: &self.errors,
+            notify_state: &self.notify_state,
+        }
+    }
+
+    /// Refresh the profile the way the app does and format the tray title from the result
+    async fn refresh(&self) -> Result<(String, Vec<notify::Alert>), FetchError> {
+        let refreshed = self.pipeline().refresh(&profile(), None).await?;
+        let title = format_tray_title(&refreshed.usage, &*self.settings.lock().await);
+        Ok((title, refreshed.alerts))
+    }
+}
+
+#[tokio::test]
+async fn normal_usage_sets_title_without_alerts() {
+    let server = MockServer::start(vec![MockResponse::usage(42.0, 18.0)]).await.unwrap();
+    let app = App::new("normal", &server, AppSettings::default());
+
+    let (title, alerts) = app.refresh().await.unwrap();
+
+    assert_eq!(title, "42%");
+    assert!(alerts.is_empty());
+    assert_eq!(app.history.query(Some(PROFILE), None, None).unwrap().len(), 1, "the fetch is recorded");
+}
+
+#[tokio::test]
+async fn crossing_threshold_alerts_once_and_rearms_after_a_reset() {
+    let server = MockServer::start(vec![
+        MockResponse::usage(85.0, 18.0),
+        MockResponse::usage(88.0, 18.0),
+        MockResponse::usage(10.0, 18.0),
+        MockResponse::NullResetsAt {
+            five_hour: 0.0,
+            seven_day: 18.0,
+        },
+        MockResponse::usage(82.0, 18.0),
+    ])
+    .await
+    .unwrap();
+    let app = App::new("threshold", &server, AppSettings::default());
+
+    let (title, alerts) = app.refresh().await.unwrap();
+    assert_eq!(title, "85%");
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].body, "Session usage at 85%");
+
+    let (_, alerts) = app.refresh().await.unwrap();
+    assert!(alerts.is_empty(), "same threshold must not alert twice");
+
+    let (title, alerts) = app.refresh().await.unwrap();
+    assert_eq!(title, "10%");
+    assert!(alerts.is_empty(), "a drop within the cycle doesn't re-arm");
+
+    // After a reset claude.ai reports no reset time until the window is used again
+    let (_, alerts) = app.refresh().await.unwrap();
+    assert!(alerts.is_empty());
+
+    let (_, alerts) = app.refresh().await.unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(server.requests(), 5);
+}
+
+#[tokio::test]
+async fn both_display_shows_session_and_weekly() {
+    let server = MockServer::start(vec![MockResponse::usage(7.0, 18.0)]).await.unwrap();
+    let settings = AppSettings {
+        menu_bar_display: MenuBarDisplay::Both,
+        show_percent_symbol: false,
+        ..AppSettings::default()
+    };
+
+    let (title, _) = App::new("both", &server, settings).refresh().await.unwrap();
+
+    assert_eq!(title, "7/18");
+}
+
+#[tokio::test]
+async fn missing_windows_render_placeholder_title() {
+    let server = MockServer::start(vec![MockResponse::missing_windows()]).await.unwrap();
+
+    let (title, alerts) = App::new("missing", &server, AppSettings::default()).refresh().await.unwrap();
+
+    assert_eq!(title, "--");
+    assert!(alerts.is_empty());
+}
+
+#[tokio::test]
+async fn null_resets_at_is_tolerated() {
+    let server = MockServer::start(vec![MockResponse::NullResetsAt {
+        five_hour: 90.0,
+        seven_day: 18.0,
+    }])
+    .await
+    .unwrap();
+
+    let usage = fetch(&server).await.unwrap();
+
+    let five_hour = usage.five_hour.unwrap();
+    assert!(five_hour.resets_at.is_empty());
+    assert!((five_hour.utilization - 90.0).abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+async fn error_responses_map_to_typed_errors() {
+    let cases = [
+        (MockResponse::Status(401), FetchError::SessionExpired),
+        (MockResponse::Status(403), FetchError::SessionExpired),
+        (MockResponse::Status(404), FetchError::OrgNotFound),
+        (
+            MockResponse::RateLimited { retry_after: Some(30) },
+            FetchError::RateLimited { retry_after: Some(30) },
+        ),
+        (
+            MockResponse::RateLimited { retry_after: None },
+            FetchError::RateLimited { retry_after: None },
+        ),
+        (MockResponse::Status(500), FetchError::Server { status: 500 }),
+        (MockResponse::Status(503), FetchError::Server { status: 503 }),
+    ];
+
+    for (response, expected) in cases {
+        let server = MockServer::start(vec![response]).await.unwrap();
+        let app = App::new("errors", &server, AppSettings::default());
+
+        let result = app.refresh().await;
+
+        assert_eq!(result.unwrap_err(), expected);
+        assert_eq!(app.errors.lock().await.get(PROFILE), Some(&expected), "kept for the tray");
+    }
+}
+
+#[tokio::test]
+async fn malformed_json_is_a_schema_change() {
+    let server = MockServer::start(vec![MockResponse::Malformed]).await.unwrap();
+
+    let error = fetch(&server).await.unwrap_err();
+
+    assert!(matches!(error, FetchError::SchemaChanged(_)), "{error:?}");
+}
+
+#[tokio::test]
+async fn unreachable_server_is_offline() {
+    let server = MockServer::start(Vec::new()).await.unwrap();
+    let base_url = server.base_url();
+    drop(server);
+    tokio::task::yield_now().await;
+
+    let client = reqwest::Client::new();
+    let error = claude::fetch_usage(&base_url, ORG_ID, SESSION_KEY, &client).await.unwrap_err();
+
+    assert_eq!(error, FetchError::Offline);
+}
+
+#[tokio::test]
+async fn failed_refresh_marks_title_and_keeps_last_numbers() {
+    let server = MockServer::start(vec![MockResponse::usage(42.0, 18.0), MockResponse::Status(401)])
+        .await
+        .unwrap();
+    let app = App::new("failed", &server, AppSettings::default());
+    let settings = app.settings.lock().await.clone();
+    let set = ProfileSet {
+        active: Some(PROFILE.to_string()),
+        profiles: vec![profile()],
+    };
+
+    app.refresh().await.unwrap();
+    assert_eq!(tray_title(&set, &*app.usage.lock().await, &*app.errors.lock().await, &settings), "42%");
+
+    app.refresh().await.unwrap_err();
+    let errors = app.errors.lock().await;
+    assert_eq!(tray_title(&set, &*app.usage.lock().await, &errors, &settings), "⚠ 42%?");
+    assert_eq!(errors[PROFILE].to_string(), "Session key expired — update in Settings");
+}
+
+#[tokio::test]
+async fn old_usage_is_marked_stale() {
+    let server = MockServer::start(vec![MockResponse::usage(42.0, 18.0)]).await.unwrap();
+    let settings = AppSettings::default();
+    let set = ProfileSet {
+        active: Some(PROFILE.to_string()),
+        profiles: vec![Profile {
+            name: PROFILE.to_string(),
+            org_id: ORG_ID.to_string(),
+            session_key: SESSION_KEY.to_string(),
+            expired: false,
+        }],
+    };
+    let mut usage = HashMap::new();
+    usage.insert(PROFILE.to_string(), fetch(&server).await.unwrap());
+    assert_eq!(tray_title(&set, &usage, &HashMap::new(), &settings), "42%");
+
+    // Fetched just before the laptop slept through the night
+    let data = usage.get_mut(PROFILE).unwrap();
+    data.fetched_at = data.fetched_at.map(|t| t - chrono::Duration::hours(8));
+    assert_eq!(tray_title(&set, &usage, &HashMap::new(), &settings), "42%?");
+
+    let parentheses = AppSettings { stale_marker: StaleMarker::Parentheses, ..settings.clone() };
+    assert_eq!(tray_title(&set, &usage, &HashMap::new(), &parentheses), "(42%)");
+    let off = AppSettings { stale_marker: StaleMarker::Off, ..settings.clone() };
+    assert_eq!(tray_title(&set, &usage, &HashMap::new(), &off), "42%");
+
+    // Eight hours is within 40 hourly intervals
+    let patient = AppSettings { refresh_interval: 60, stale_after_intervals: 40, ..settings.clone() };
+    assert_eq!(tray_title(&set, &usage, &HashMap::new(), &patient), "42%");
+
+    // Without a fetch time there is nothing to vouch for the numbers
+    usage.get_mut(PROFILE).unwrap().fetched_at = None;
+    assert_eq!(tray_title(&set, &usage, &HashMap::new(), &settings), "42%?");
+}
+
+#[test]
+fn last_update_is_described_relative_to_now() {
+    let now = chrono::Utc::now();
+
+    assert_eq!(format_updated(Some(now - chrono::Duration::seconds(20)), now), "Updated just now");
+    assert_eq!(format_updated(Some(now - chrono::Duration::minutes(3)), now), "Updated 3m ago");
+    assert_eq!(format_updated(Some(now - chrono::Duration::minutes(135)), now), "Updated 2h 15m ago");
+    assert_eq!(format_updated(None, now), "Last update unknown");
+}
+
+#[tokio::test]
+async fn organizations_are_discovered_from_the_session_key() {
+    let server = MockServer::start(Vec::new()).await.unwrap();
+    let client = reqwest::Client::new();
+
+    let orgs = claude::fetch_organizations(&server.base_url(), SESSION_KEY, &client).await.unwrap();
+
+    let summary: Vec<_> = orgs.iter().map(|o| (o.name.as_str(), o.plan.as_str())).collect();
+    assert_eq!(summary, [("Personal", "Pro"), ("Team Max", "Max 20x")]);
+    assert_eq!(orgs[0].uuid, "11111111-1111-1111-1111-111111111111");
+    assert_eq!(server.requests(), 0, "only usage requests consume the script");
+}
+
+#[tokio::test]
+async fn login_redirect_expires_the_profile() {
+    let server = MockServer::start(vec![MockResponse::RedirectToLogin]).await.unwrap();
+    let mut set = ProfileSet::default();
+    set.upsert(
+        Profile {
+            name: PROFILE.to_string(),
+            org_id: ORG_ID.to_string(),
+            session_key: SESSION_KEY.to_string(),
+            expired: false,
+        },
+        None,
+    )
+    .unwrap();
+
+    let error = fetch(&server).await.unwrap_err();
+    assert_eq!(error, FetchError::SessionExpired);
+
+    assert!(set.mark_expired(PROFILE));
+    assert!(!set.mark_expired(PROFILE), "expiry is only reported once");
+    assert!(!set.active_profile().unwrap().should_poll());
+
+    let title = tray_title(&set, &HashMap::new(), &HashMap::new(), &AppSettings::default());
+    assert_eq!(title, "🔑 auth needed");
+
+    // Saving the same key keeps the flag, a new key clears it
+    set.upsert(set.profiles[0].clone(), None).unwrap();
+    assert!(set.profiles[0].expired);
+    set.upsert(
+        Profile {
+            session_key: "sk-ant-sid01-new".to_string(),
+            expired: false,
+            ..set.profiles[0].clone()
+        },
+        None,
+    )
+    .unwrap();
+    assert!(set.active_profile().unwrap().should_poll());
+}
@@ -0,0 +1,47 @@
+use seekers_lib::mock::MockServer;
+use seekers_lib::resume::{self, ClockWatch, NetworkWatch};
+use std::time::{Duration, Instant, SystemTime};
+
+#[test]
+fn wall_clock_jumps_are_reported_as_wake_ups() {
+    let start = Instant::now();
+    let wall = SystemTime::now();
+    let mut clock = ClockWatch::starting_at(start, wall);
+
+    let tick = Duration::from_secs(10);
+    assert_eq!(clock.check_at(start + tick, wall + tick), None, "clocks in step");
+
+    // Asleep for two hours: the monotonic clock only moved by one tick
+    let slept = Duration::from_hours(2);
+    let jump = clock.check_at(start + tick * 2, wall + tick * 2 + slept);
+    assert_eq!(jump, Some(slept));
+
+    assert_eq!(clock.check_at(start + tick * 3, wall + tick * 3 + slept), None, "reported once");
+    assert_eq!(
+        clock.check_at(start + tick * 4, wall + tick * 2),
+        None,
+        "clock set backwards"
+    );
+}
+
+#[test]
+fn only_the_first_success_after_a_failure_is_a_reconnect() {
+    let mut network = NetworkWatch::new();
+
+    assert!(!network.update(true));
+    assert!(!network.update(false));
+    assert!(!network.update(false));
+    assert!(network.update(true));
+    assert!(!network.update(true));
+}
+
+#[tokio::test]
+async fn probes_the_api_host() {
+    let server = MockServer::start(Vec::new()).await.unwrap();
+    assert!(resume::reachable(&server.base_url()).await);
+
+    // A port that was just free is almost certainly still closed
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    assert!(!resume::reachable(&format!("http://127.0.0.1:{port}/api")).await);
+    assert!(!resume::reachable("not a url").await);
+}
@@ -0,0 +1,107 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use seekers_lib::error::FetchError;
+use seekers_lib::forecast::Forecast;
+use seekers_lib::scheduler::{until_next_reset, Pace, Scheduler};
+use seekers_lib::{UsageData, UsageWindow};
+use std::time::Duration;
+
+const INTERVAL: Duration = Duration::from_mins(15);
+
+fn usage(utilization: f64, rate_per_hour: Option<f64>, resets_in: ChronoDuration) -> UsageData {
+    let window = UsageWindow {
+        utilization,
+        resets_at: (Utc::now() + resets_in).to_rfc3339(),
+        forecast: rate_per_hour.map(|rate_per_hour| Forecast {
+            rate_per_hour,
+            limit_at: None,
+        }),
+    };
+    UsageData {
+        five_hour: Some(window),
+        seven_day: None,
+        fetched_at: Some(Utc::now()),
+    }
+}
+
+#[test]
+fn steady_usage_keeps_the_configured_interval() {
+    let data = usage(40.0, Some(5.0), ChronoDuration::hours(3));
+
+    let next = Scheduler::new().next_delay(INTERVAL, &[], [&data], Utc::now());
+
+    assert_eq!(next, (INTERVAL, Pace::Normal));
+}
+
+#[test]
+fn network_errors_back_off_exponentially_and_recover() {
+    let mut scheduler = Scheduler::new();
+    let data = usage(40.0, None, ChronoDuration::hours(3));
+    let offline = [FetchError::Offline];
+
+    let delays: Vec<_> = (0..4)
+        .map(|_| scheduler.next_delay(INTERVAL, &offline, [&data], Utc::now()))
+        .collect();
+    assert_eq!(delays[0], (INTERVAL, Pace::Backoff));
+    assert_eq!(delays[1], (INTERVAL * 2, Pace::Backoff));
+    assert_eq!(delays[2], (INTERVAL * 4, Pace::Backoff));
+    assert_eq!(delays[3].0, Duration::from_hours(1), "backoff is capped");
+
+    let next = scheduler.next_delay(INTERVAL, &[], [&data], Utc::now());
+    assert_eq!(next, (INTERVAL, Pace::Normal), "a success resets the backoff");
+}
+
+#[test]
+fn retry_after_is_honored() {
+    let data = usage(40.0, None, ChronoDuration::hours(3));
+    let limited = [FetchError::RateLimited { retry_after: Some(45 * 60) }];
+
+    let next = Scheduler::new().next_delay(INTERVAL, &limited, [&data], Utc::now());
+
+    assert_eq!(next, (Duration::from_mins(45), Pace::RetryAfter));
+}
+
+#[test]
+fn expired_keys_do_not_back_off() {
+    let data = usage(40.0, None, ChronoDuration::hours(3));
+
+    let next = Scheduler::new().next_delay(INTERVAL, &[FetchError::SessionExpired], [&data], Utc::now());
+
+    assert_eq!(next.1, Pace::Normal);
+}
+
+#[test]
+fn fast_climb_or_imminent_reset_speeds_up() {
+    let climbing = usage(60.0, Some(30.0), ChronoDuration::hours(3));
+    let resetting = usage(60.0, Some(5.0), ChronoDuration::minutes(10));
+
+    let mut scheduler = Scheduler::new();
+    assert_eq!(scheduler.next_delay(INTERVAL, &[], [&climbing], Utc::now()), (INTERVAL / 3, Pace::Fast));
+    assert_eq!(scheduler.next_delay(INTERVAL, &[], [&resetting], Utc::now()).1, Pace::Fast);
+
+    let short = Duration::from_mins(2);
+    let (delay, _) = scheduler.next_delay(short, &[], [&climbing], Utc::now());
+    assert_eq!(delay, Duration::from_mins(1), "never faster than the minimum");
+}
+
+#[test]
+fn idle_usage_slows_down() {
+    let idle = usage(12.0, Some(0.0), ChronoDuration::hours(3));
+    let unknown = usage(12.0, None, ChronoDuration::hours(3));
+
+    let mut scheduler = Scheduler::new();
+    assert_eq!(scheduler.next_delay(INTERVAL, &[], [&idle], Utc::now()), (INTERVAL * 2, Pace::Slow));
+    assert_eq!(scheduler.next_delay(INTERVAL, &[], [&idle, &unknown], Utc::now()).1, Pace::Normal);
+}
+
+#[test]
+fn next_reset_wakes_just_after_the_earliest_window_in_use() {
+    let now = Utc::now();
+    let soon = usage(30.0, None, ChronoDuration::minutes(20));
+    let unused = usage(0.0, None, ChronoDuration::minutes(5));
+    let later = usage(30.0, None, ChronoDuration::hours(2));
+
+    let wait = until_next_reset([&later, &soon, &unused], now).unwrap();
+
+    assert!(wait > Duration::from_mins(20) && wait < Duration::from_mins(21), "{wait:?}");
+    assert_eq!(until_next_reset([&unused], now), None, "a window at 0% has nothing to reset");
+}
@@ -0,0 +1,122 @@
+use seekers_lib::channels::ChannelSecret;
+use seekers_lib::notify::RuleCondition;
+use seekers_lib::settings::{AppSettings, MenuBarDisplay, ProgressStyle, TrayProfile, CURRENT_VERSION};
+use seekers_lib::WindowKind;
+
+#[test]
+fn unversioned_file_is_migrated() {
+    let json = r#"{
+        "menuBarDisplay": "Both",
+        "showPercentSymbol": false,
+        "progressStyle": "blocks",
+        "progressLength": 8,
+        "refreshInterval": 5,
+        "notifySession": 90,
+        "notifyWeekly": 70
+    }"#;
+
+    let settings = AppSettings::parse(json).unwrap();
+
+    assert_eq!(settings.version, CURRENT_VERSION);
+    assert_eq!(settings.menu_bar_display, MenuBarDisplay::Both);
+    assert_eq!(settings.progress_style, ProgressStyle::Blocks);
+    assert_eq!(settings.refresh_interval, 5);
+    assert_eq!(settings.tray_profile, TrayProfile::Active, "missing field takes its default");
+}
+
+#[test]
+fn invalid_fields_are_reset_individually() {
+    let json = r#"{"version": 2, "menuBarDisplay": "sideways", "refreshInterval": "soon", "notifySession": 95, "someFutureField": true}"#;
+
+    let settings = AppSettings::parse(json).unwrap();
+
+    assert_eq!(settings.menu_bar_display, MenuBarDisplay::Session);
+    assert_eq!(settings.refresh_interval, AppSettings::default().refresh_interval);
+    assert_eq!(settings.notification_rules[0].condition, RuleCondition::Crosses { percent: 95 });
+}
+
+#[test]
+fn fixed_thresholds_become_rules() {
+    let json = r#"{"version": 2, "notifySession": 90, "notifyWeekly": 0}"#;
+
+    let settings = AppSettings::parse(json).unwrap();
+
+    let rules: Vec<_> = settings
+        .notification_rules
+        .iter()
+        .map(|r| (r.id.as_str(), r.window, r.enabled, r.condition.clone()))
+        .collect();
+    assert_eq!(
+        rules,
+        [
+            ("session", WindowKind::FiveHour, true, RuleCondition::Crosses { percent: 90 }),
+            ("weekly", WindowKind::SevenDay, false, RuleCondition::Crosses { percent: 80 }),
+        ]
+    );
+    assert!(!serde_json::to_string(&settings).unwrap().contains("notifySession"));
+}
+
+#[test]
+fn channel_secrets_are_kept_apart_by_channel_id() {
+    let json = r#"{"version": 3, "channels": [
+        {"name": "Phone", "kind": "ntfy", "url": "https://ntfy.sh/secret-topic", "token": "tk_1"},
+        {"id": "channel-1", "name": "Team", "kind": "slack", "url": "https://hooks.slack.com/x"}
+    ]}"#;
+    let mut settings = AppSettings::parse(json).unwrap();
+    let ids: Vec<_> = settings.channels.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, ["channel-2", "channel-1"], "missing IDs don't collide with existing ones");
+
+    let secrets = settings.take_channel_secrets();
+    assert!(!serde_json::to_string(&settings).unwrap().contains("secret-topic"));
+    assert_eq!(
+        secrets["channel-2"],
+        ChannelSecret {
+            url: "https://ntfy.sh/secret-topic".to_string(),
+            token: Some("tk_1".to_string()),
+        }
+    );
+
+    let mut reloaded = AppSettings::parse(&serde_json::to_string(&settings).unwrap()).unwrap();
+    reloaded.apply_channel_secrets(&secrets);
+    assert_eq!(reloaded.channels[0].token.as_deref(), Some("tk_1"));
+    assert_eq!(reloaded.channels[1].url, "https://hooks.slack.com/x");
+}
+
+#[test]
+fn saved_settings_round_trip() {
+    let settings = AppSettings {
+        progress_style: ProgressStyle::Dots,
+        tray_profile: TrayProfile::Aggregate,
+        ..AppSettings::default()
+    };
+
+    let json = serde_json::to_string(&settings).unwrap();
+    assert!(json.contains(r#""trayProfile":"aggregate""#));
+
+    let parsed = AppSettings::parse(&json).unwrap();
+    assert_eq!(parsed.progress_style, ProgressStyle::Dots);
+    assert_eq!(parsed.tray_profile, TrayProfile::Aggregate);
+}
+
+#[test]
+fn malformed_json_is_an_error() {
+    assert!(AppSettings::parse("{\"menuBarDisplay\": ").is_err());
+}
+
+#[test]
+fn invalid_fields_keep_the_current_value_and_are_reported() {
+    let current = AppSettings {
+        refresh_interval: 5,
+        menu_bar_display: MenuBarDisplay::Both,
+        ..AppSettings::default()
+    };
+    let json = r#"{"version": 3, "refreshInterval": "soon", "progressStyle": "blocks"}"#;
+
+    let (settings, rejected) = AppSettings::parse_over(json, &current).unwrap();
+
+    assert_eq!(settings.refresh_interval, 5, "kept");
+    assert_eq!(settings.progress_style, ProgressStyle::Blocks, "applied");
+    assert_eq!(settings.menu_bar_display, MenuBarDisplay::Session, "missing fields still take their default");
+    assert_eq!(rejected.len(), 1);
+    assert!(rejected[0].starts_with("refreshInterval ("), "{rejected:?}");
+}
@@ -8,15 +8,121 @@ interface Credentials {
   sessionKey: string;
 }
 
+interface Profile {
+  name: string;
+  orgId: string;
+  sessionKey: string;
+  expired?: boolean;
+}
+
+interface Organization {
+  uuid: string;
+  name: string;
+  plan: string;
+}
+
+interface ProfileSet {
+  active: string | null;
+  profiles: Profile[];
+}
+
+interface Forecast {
+  ratePerHour: number;
+  limitAt: string | null;
+}
+
+interface UsageWindow {
+  utilization: number;
+  resetsAt: string;
+  forecast?: Forecast;
+}
+
 interface UsageData {
-  fiveHour: {
-    utilization: number;
-    resetsAt: string;
-  } | null;
-  sevenDay: {
-    utilization: number;
-    resetsAt: string;
-  } | null;
+  fiveHour: UsageWindow | null;
+  sevenDay: UsageWindow | null;
+  fetchedAt?: string;
+}
+
+interface UsageError {
+  profile: string;
+  kind: string;
+  message: string;
+  retryAfter: number | null;
+}
+
+interface WindowReset {
+  profile: string;
+  window: "fiveHour" | "sevenDay";
+  resetsAt: string;
+}
+
+interface ConfigError {
+  file: string;
+  message: string | null;
+}
+
+type RuleCondition =
+  | { type: "crosses"; percent: number }
+  | { type: "escalating"; percents: number[] }
+  | { type: "projectedLimit"; minutes: number }
+  | { type: "reset" }
+  | { type: "rateAbove"; percentPerHour: number };
+
+interface NotificationRule {
+  id: string;
+  enabled: boolean;
+  window: "fiveHour" | "sevenDay";
+  condition: RuleCondition;
+  cooldownMinutes: number;
+  quietHours?: { start: string; end: string };
+  message: string;
+}
+
+type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+const WEEKDAYS: Weekday[] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+interface QuietPeriod {
+  days: Weekday[];
+  start: string;
+  end: string;
+}
+
+interface QuietSchedule {
+  enabled: boolean;
+  mode: "digest" | "drop";
+  periods: QuietPeriod[];
+}
+
+type ChannelKind = "webhook" | "slack" | "discord" | "ntfy" | "gotify";
+
+interface ChannelConfig {
+  id: string;
+  name: string;
+  enabled: boolean;
+  kind: ChannelKind;
+  url: string;
+  token?: string;
+  title: string;
+  message: string;
+  retries: number;
+}
+
+interface ChannelError {
+  channel: string;
+  message: string;
+}
+
+interface ApiSettings {
+  enabled: boolean;
+  port: number;
+  metrics: boolean;
+}
+
+interface ApiInfo {
+  url: string | null;
+  token: string;
+  error: string | null;
 }
 
 interface AppSettings {
@@ -25,8 +131,13 @@ interface AppSettings {
   progressStyle: "circles" | "blocks" | "bar" | "dots";
   progressLength: 5 | 8 | 10;
   refreshInterval: 0 | 5 | 15 | 30 | 60;
-  notifySession: number;
-  notifyWeekly: number;
+  notificationRules: NotificationRule[];
+  quietHours: QuietSchedule;
+  channels: ChannelConfig[];
+  api: ApiSettings;
+  trayProfile: "active" | "aggregate";
+  staleMarker: "question" | "parentheses" | "off";
+  staleAfterIntervals: number;
 }
 
 type Tab = "account" | "appearance" | "about";
@@ -37,10 +148,26 @@ const defaultSettings: AppSettings = {
   progressStyle: "circles",
   progressLength: 10,
   refreshInterval: 15,
-  notifySession: 80,
-  notifyWeekly: 80,
+  notificationRules: [
+    { id: "session", enabled: true, window: "fiveHour", condition: { type: "escalating", percents: [50, 75, 90, 100] }, cooldownMinutes: 0, message: "" },
+    { id: "weekly", enabled: true, window: "sevenDay", condition: { type: "escalating", percents: [50, 75, 90, 100] }, cooldownMinutes: 0, message: "" },
+    { id: "session-reset", enabled: false, window: "fiveHour", condition: { type: "reset" }, cooldownMinutes: 0, message: "" },
+  ],
+  quietHours: {
+    enabled: false,
+    mode: "digest",
+    periods: [{ days: [...WEEKDAYS], start: "22:00", end: "07:00" }],
+  },
+  channels: [],
+  api: { enabled: false, port: 8788, metrics: false },
+  trayProfile: "active",
+  staleMarker: "question",
+  staleAfterIntervals: 3,
 };
 
+/** Usage is never stale younger than this, matching the tray */
+const STALE_MIN_MINUTES = 30;
+
 function App() {
   const [tab, setTab] = useState<Tab>("account");
   const [credentials, setCredentials] = useState<Credentials>({
@@ -52,16 +179,65 @@ function App() {
   const [status, setStatus] = useState<"idle" | "saving" | "saved">("idle");
   const [settings, setSettings] = useState<AppSettings>(defaultSettings);
   const [version, setVersion] = useState<string>("");
+  const [profiles, setProfiles] = useState<ProfileSet>({ active: null, profiles: [] });
+  const [profileName, setProfileName] = useState("");
+  const [profileError, setProfileError] = useState<string | null>(null);
+  const [usageError, setUsageError] = useState<UsageError | null>(null);
+  const [configErrors, setConfigErrors] = useState<Record<string, string>>({});
+  const [organizations, setOrganizations] = useState<Organization[]>([]);
+  const [discovering, setDiscovering] = useState(false);
+  const [discoverError, setDiscoverError] = useState<string | null>(null);
+  const [now, setNow] = useState(() => Date.now());
+  const [resetNotice, setResetNotice] = useState<WindowReset | null>(null);
+  const [channelErrors, setChannelErrors] = useState<Record<string, string>>({});
+  const [apiInfo, setApiInfo] = useState<ApiInfo | null>(null);
+  const [apiPort, setApiPort] = useState(String(defaultSettings.api.port));
 
   useEffect(() => {
     loadCredentials();
+    loadProfiles();
     loadSettings();
     getVersion().then(setVersion);
     const unlisten = listen<UsageData>("usage-updated", (event) => {
       setUsage(event.payload);
+      setUsageError(null);
+    });
+    const unlistenError = listen<UsageError>("usage-error", (event) => {
+      setUsageError(event.payload);
     });
+    const unlistenSettings = listen<AppSettings>("settings-changed", (event) => {
+      setSettings(event.payload);
+      loadApiInfo(event.payload.api);
+    });
+    const unlistenProfiles = listen("profiles-changed", () => {
+      loadProfiles();
+    });
+    const unlistenConfig = listen<ConfigError>("config-error", (event) => {
+      const { file, message } = event.payload;
+      setConfigErrors((errors) => {
+        const next = { ...errors };
+        if (message) next[file] = message;
+        else delete next[file];
+        return next;
+      });
+    });
+    const unlistenReset = listen<WindowReset>("window-reset", (event) => {
+      setResetNotice(event.payload);
+    });
+    const unlistenChannel = listen<ChannelError>("channel-error", (event) => {
+      const { channel, message } = event.payload;
+      setChannelErrors((errors) => ({ ...errors, [channel]: message }));
+    });
+    const clock = setInterval(() => setNow(Date.now()), 30_000);
     return () => {
+      clearInterval(clock);
       unlisten.then((fn) => fn());
+      unlistenError.then((fn) => fn());
+      unlistenSettings.then((fn) => fn());
+      unlistenProfiles.then((fn) => fn());
+      unlistenConfig.then((fn) => fn());
+      unlistenReset.then((fn) => fn());
+      unlistenChannel.then((fn) => fn());
     };
   }, []);
 
@@ -69,6 +245,7 @@ function App() {
     try {
       const s = await invoke<AppSettings>("get_settings");
       setSettings(s);
+      loadApiInfo(s.api);
     } catch (e) {
       console.error("Failed to load settings:", e);
     }
@@ -83,6 +260,76 @@ function App() {
     }
   }
 
+  function updateRule(index: number, rule: NotificationRule) {
+    const notificationRules = settings.notificationRules.map((r, i) => (i === index ? rule : r));
+    updateSettings({ ...settings, notificationRules });
+  }
+
+  function removeRule(index: number) {
+    updateSettings({ ...settings, notificationRules: settings.notificationRules.filter((_, i) => i !== index) });
+  }
+
+  function addRule() {
+    const rule: NotificationRule = {
+      id: `rule-${Date.now()}`,
+      enabled: true,
+      window: "fiveHour",
+      condition: { type: "crosses", percent: 90 },
+      cooldownMinutes: 0,
+      message: "",
+    };
+    updateSettings({ ...settings, notificationRules: [...settings.notificationRules, rule] });
+  }
+
+  function updateQuietHours(quietHours: Partial<QuietSchedule>) {
+    updateSettings({ ...settings, quietHours: { ...settings.quietHours, ...quietHours } });
+  }
+
+  function updatePeriod(index: number, period: QuietPeriod) {
+    updateQuietHours({ periods: settings.quietHours.periods.map((p, i) => (i === index ? period : p)) });
+  }
+
+  async function loadApiInfo(api: ApiSettings) {
+    setApiPort(String(api.port));
+    setApiInfo(api.enabled ? await invoke<ApiInfo>("get_api_info").catch(() => null) : null);
+  }
+
+  async function updateApi(api: ApiSettings) {
+    await updateSettings({ ...settings, api });
+    await loadApiInfo(api);
+  }
+
+  function savePort() {
+    const port = Number(apiPort);
+    if (Number.isInteger(port) && port > 0 && port < 65536 && port !== settings.api.port) {
+      updateApi({ ...settings.api, port });
+    } else {
+      setApiPort(String(settings.api.port));
+    }
+  }
+
+  function updateChannel(index: number, channel: ChannelConfig) {
+    updateSettings({ ...settings, channels: settings.channels.map((c, i) => (i === index ? channel : c)) });
+  }
+
+  function removeChannel(index: number) {
+    updateSettings({ ...settings, channels: settings.channels.filter((_, i) => i !== index) });
+  }
+
+  function addChannel() {
+    const channel: ChannelConfig = {
+      id: `channel-${Date.now()}`,
+      name: `Channel ${settings.channels.length + 1}`,
+      enabled: true,
+      kind: "webhook",
+      url: "",
+      title: "",
+      message: "",
+      retries: 3,
+    };
+    updateSettings({ ...settings, channels: [...settings.channels, channel] });
+  }
+
   const [credentialsDirty, setCredentialsDirty] = useState(false);
 
   async function saveCredentials() {
@@ -97,6 +344,7 @@ function App() {
         sessionKey: credentials.sessionKey,
       });
       await invoke("refresh_usage");
+      await loadProfiles();
       setStatus("saved");
       setTimeout(() => setStatus("idle"), 1500);
     } catch (e) {
@@ -105,16 +353,105 @@ function App() {
     }
   }
 
+  function selectOrganization(uuid: string) {
+    setCredentials((c) => ({ ...c, orgId: uuid }));
+    setCredentialsDirty(true);
+  }
+
+  async function findOrganizations() {
+    if (!credentials.sessionKey) return;
+    setDiscovering(true);
+    setDiscoverError(null);
+    try {
+      const orgs = await invoke<Organization[]>("discover_organizations", {
+        sessionKey: credentials.sessionKey,
+      });
+      setOrganizations(orgs);
+      if (orgs.length === 0) {
+        setDiscoverError("No organizations found for this session key");
+      } else if (orgs.length === 1 || !credentials.orgId) {
+        selectOrganization(orgs[0].uuid);
+      }
+    } catch (e) {
+      setOrganizations([]);
+      setDiscoverError(String(e));
+    } finally {
+      setDiscovering(false);
+    }
+  }
+
   useEffect(() => {
     if (!credentialsDirty) return;
     const timeout = setTimeout(saveCredentials, 2000);
     return () => clearTimeout(timeout);
   }, [credentials.orgId, credentials.sessionKey, credentialsDirty]);
 
+  async function loadProfiles() {
+    try {
+      const set = await invoke<ProfileSet>("get_profiles");
+      setProfiles(set);
+      setProfileName(set.active ?? set.profiles[0]?.name ?? "");
+    } catch (e) {
+      console.error("Failed to load profiles:", e);
+    }
+  }
+
+  const activeProfile = profiles.active ?? profiles.profiles[0]?.name ?? null;
+
+  async function switchProfile(name: string) {
+    try {
+      await invoke("set_active_profile", { name });
+      await Promise.all([loadProfiles(), loadCredentials()]);
+    } catch (e) {
+      console.error("Failed to switch profile:", e);
+    }
+  }
+
+  async function addProfile() {
+    let n = profiles.profiles.length + 1;
+    while (profiles.profiles.some((p) => p.name === `Profile ${n}`)) n++;
+    const name = `Profile ${n}`;
+    try {
+      await invoke("save_profile", { profile: { name, orgId: "", sessionKey: "" } });
+      await switchProfile(name);
+    } catch (e) {
+      console.error("Failed to add profile:", e);
+    }
+  }
+
+  async function renameProfile() {
+    const name = profileName.trim();
+    const stored = profiles.profiles.find((p) => p.name === activeProfile);
+    if (!stored || !name || name === activeProfile) return;
+    try {
+      await invoke("save_profile", {
+        profile: { ...stored, name },
+        previousName: activeProfile,
+      });
+      setProfileError(null);
+      await loadProfiles();
+    } catch (e) {
+      setProfileError(String(e));
+      setProfileName(activeProfile);
+    }
+  }
+
+  async function removeProfile() {
+    if (!activeProfile) return;
+    try {
+      await invoke("delete_profile", { name: activeProfile });
+      await Promise.all([loadProfiles(), loadCredentials()]);
+    } catch (e) {
+      console.error("Failed to remove profile:", e);
+    }
+  }
+
   async function loadCredentials() {
     try {
       const creds = await invoke<Credentials>("get_credentials");
       setCredentials(creds);
+      setOrganizations([]);
+      setDiscoverError(null);
     } catch (e) {
       console.error("Failed to load credentials:", e);
     } finally {
@@ -143,6 +480,26 @@ function App() {
     return "soon";
   }
 
+  function formatForecast(forecast?: Forecast): string | undefined {
+    if (!forecast?.limitAt) return undefined;
+    return `At this pace: limit in ${formatResetTime(forecast.limitAt)}`;
+  }
+
+  function formatUpdated(fetchedAt?: string): string {
+    if (!fetchedAt) return "Last update unknown";
+    const mins = Math.floor((now - new Date(fetchedAt).getTime()) / (1000 * 60));
+    if (mins < 1) return "Updated just now";
+    if (mins < 60) return `Updated ${mins}m ago`;
+    const hours = Math.floor(mins / 60);
+    return mins % 60 ? `Updated ${hours}h ${mins % 60}m ago` : `Updated ${hours}h ago`;
+  }
+
+  function isStale(data: UsageData): boolean {
+    if (usageError || !data.fetchedAt) return true;
+    const limitMins = Math.max(settings.refreshInterval * settings.staleAfterIntervals, STALE_MIN_MINUTES);
+    return now - new Date(data.fetchedAt).getTime() > limitMins * 60 * 1000;
+  }
+
   function getBarColor(pct: number): string {
     if (pct > 80) return "bg-rose-500";
     if (pct > 50) return "bg-amber-400";
@@ -193,17 +550,49 @@ function App() {
           </div>
         </div>
 
+        {Object.entries(configErrors).map(([file, message]) => (
+          <div key={file} className="mb-4 px-3 py-2 text-[12px] text-amber-700 dark:text-amber-400 bg-amber-500/10 rounded-lg">
+            <span className="font-medium">{file}</span> was not applied: {message}
+          </div>
+        ))}
+
         {tab === "account" && (
           <div className="space-y-5">
+            {usageError && (
+              <div className="px-3 py-2 text-[12px] text-rose-600 dark:text-rose-400 bg-rose-500/10 rounded-lg">
+                {usageError.message}
+                {profiles.profiles.length > 1 && (
+                  <span className="text-rose-400 dark:text-rose-300"> ({usageError.profile})</span>
+                )}
+              </div>
+            )}
+
+            {resetNotice && (
+              <button
+                onClick={() => setResetNotice(null)}
+                className="w-full px-3 py-2 text-left text-[12px] text-emerald-700 dark:text-emerald-400 bg-emerald-500/10 rounded-lg"
+              >
+                Your {resetNotice.window === "fiveHour" ? "session" : "weekly"} limit has reset
+                {profiles.profiles.length > 1 && (
+                  <span className="text-emerald-500 dark:text-emerald-300"> ({resetNotice.profile})</span>
+                )}
+              </button>
+            )}
+
             {/* Usage Section */}
             {usage && (
-              <Section title="Usage">
-                <div className="space-y-4">
+              <Section
+                title="Usage"
+                badge={formatUpdated(usage.fetchedAt) + (isStale(usage) ? " · stale" : "")}
+                badgeColor={isStale(usage) ? "text-amber-500" : "text-gray-400"}
+              >
+                <div className={`space-y-4 ${isStale(usage) ? "opacity-50" : ""}`}>
                   {usage.fiveHour && (
                     <UsageBar
                       label="Session"
                       pct={usage.fiveHour.utilization}
                       resetTime={formatResetTime(usage.fiveHour.resetsAt)}
+                      warning={formatForecast(usage.fiveHour.forecast)}
                       color={getBarColor(usage.fiveHour.utilization)}
                     />
                   )}
@@ -212,6 +601,7 @@ function App() {
                       label="Weekly"
                       pct={usage.sevenDay.utilization}
                       resetTime={formatResetTime(usage.sevenDay.resetsAt)}
+                      warning={formatForecast(usage.sevenDay.forecast)}
                       color={getBarColor(usage.sevenDay.utilization)}
                     />
                   )}
@@ -226,23 +616,94 @@ function App() {
               badgeColor={status === "saved" ? "text-emerald-500" : "text-gray-400"}
             >
               <div className="space-y-3">
-                <Input
-                  label="Organization ID"
-                  value={credentials.orgId}
-                  onChange={(v) => { setCredentials({ ...credentials, orgId: v }); setCredentialsDirty(true); }}
-                  onBlur={saveCredentials}
-                  placeholder="xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
-                  mono
-                />
+                <Row label="Profile">
+                  <div className="flex items-center gap-1.5">
+                    {profiles.profiles.length > 1 && (
+                      <Select
+                        value={activeProfile ?? ""}
+                        onChange={switchProfile}
+                        options={profiles.profiles.map((p) => ({ value: p.name, label: p.name }))}
+                      />
+                    )}
+                    <button
+                      onClick={addProfile}
+                      className="px-2.5 py-1.5 text-[13px] text-gray-500 dark:text-gray-400 rounded-lg
+                        bg-black/[0.03] dark:bg-white/[0.06] hover:bg-black/[0.06] dark:hover:bg-white/[0.1] transition-colors"
+                    >
+                      Add
+                    </button>
+                    {profiles.profiles.length > 1 && (
+                      <button
+                        onClick={removeProfile}
+                        className="px-2.5 py-1.5 text-[13px] text-rose-500 rounded-lg
+                          bg-black/[0.03] dark:bg-white/[0.06] hover:bg-black/[0.06] dark:hover:bg-white/[0.1] transition-colors"
+                      >
+                        Remove
+                      </button>
+                    )}
+                  </div>
+                </Row>
+                {profiles.profiles.length > 0 && (
+                  <Input
+                    label="Profile Name"
+                    value={profileName}
+                    onChange={(v) => { setProfileName(v); setProfileError(null); }}
+                    onBlur={renameProfile}
+                    placeholder="Personal"
+                  />
+                )}
+                {profileError && (
+                  <p className="text-[12px] text-rose-500">{profileError}</p>
+                )}
                 <Input
                   label="Session Key"
                   value={credentials.sessionKey}
                   onChange={(v) => { setCredentials({ ...credentials, sessionKey: v }); setCredentialsDirty(true); }}
-                  onBlur={saveCredentials}
+                  onBlur={() => (credentials.orgId ? saveCredentials() : findOrganizations())}
                   placeholder="sk-ant-sid01-..."
                   type="password"
                   mono
                 />
+                <Input
+                  label="Organization ID"
+                  value={credentials.orgId}
+                  onChange={(v) => { setCredentials({ ...credentials, orgId: v }); setCredentialsDirty(true); }}
+                  onBlur={saveCredentials}
+                  placeholder="xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
+                  mono
+                />
+                <Row label="Organization">
+                  <div className="flex items-center gap-1.5">
+                    {organizations.length > 1 && (
+                      <Select
+                        value={credentials.orgId}
+                        onChange={selectOrganization}
+                        options={[
+                          ...(organizations.some((o) => o.uuid === credentials.orgId)
+                            ? []
+                            : [{ value: credentials.orgId, label: "Choose…" }]),
+                          ...organizations.map((o) => ({ value: o.uuid, label: `${o.name} · ${o.plan}` })),
+                        ]}
+                      />
+                    )}
+                    {organizations.length === 1 && (
+                      <span className="text-[12px] text-gray-500 dark:text-gray-400">
+                        {organizations[0].name} · {organizations[0].plan}
+                      </span>
+                    )}
+                    <button
+                      onClick={findOrganizations}
+                      disabled={!credentials.sessionKey || discovering}
+                      className="px-2.5 py-1.5 text-[13px] text-gray-500 dark:text-gray-400 rounded-lg disabled:opacity-50
+                        bg-black/[0.03] dark:bg-white/[0.06] hover:bg-black/[0.06] dark:hover:bg-white/[0.1] transition-colors"
+                    >
+                      {discovering ? "Finding…" : "Find"}
+                    </button>
+                  </div>
+                </Row>
+                {discoverError && (
+                  <p className="text-[12px] text-rose-500">{discoverError}</p>
+                )}
               </div>
             </Section>
 
@@ -253,7 +714,7 @@ function App() {
                 <li>Open DevTools <span className="text-[11px] text-gray-400">(Cmd+Option+I)</span></li>
                 <li>Go to Application → Cookies</li>
                 <li>Copy <code className="px-1 py-0.5 bg-black/5 dark:bg-white/10 rounded text-[11px]">sessionKey</code></li>
-                <li>Get Org ID from URL <span className="text-[11px] text-gray-400">(/organizations/...)</span></li>
+                <li>Paste it above — the organization is found automatically</li>
               </ol>
             </Section>
           </div>
@@ -275,6 +736,29 @@ function App() {
                     ]}
                   />
                 </Row>
+                {profiles.profiles.length > 1 && (
+                  <Row label="Profile shown">
+                    <Select
+                      value={settings.trayProfile}
+                      onChange={(v) => updateSettings({ ...settings, trayProfile: v as AppSettings["trayProfile"] })}
+                      options={[
+                        { value: "active", label: "Active profile" },
+                        { value: "aggregate", label: "Highest of all" },
+                      ]}
+                    />
+                  </Row>
+                )}
+                <Row label="Stale marker">
+                  <Select
+                    value={settings.staleMarker}
+                    onChange={(v) => updateSettings({ ...settings, staleMarker: v as AppSettings["staleMarker"] })}
+                    options={[
+                      { value: "question", label: "Question mark (42%?)" },
+                      { value: "parentheses", label: "Dimmed (42%)" },
+                      { value: "off", label: "Off" },
+                    ]}
+                  />
+                </Row>
                 <Row label="Show % symbol">
                   <Toggle
                     checked={settings.showPercentSymbol}
@@ -318,49 +802,52 @@ function App() {
             </Section>
 
             <Section title="Refresh">
-              <Row label="Auto-refresh">
-                <Select
-                  value={String(settings.refreshInterval)}
-                  onChange={(v) => updateSettings({ ...settings, refreshInterval: Number(v) as AppSettings["refreshInterval"] })}
-                  options={[
-                    { value: "0", label: "Manual" },
-                    { value: "5", label: "5 min" },
-                    { value: "15", label: "15 min" },
-                    { value: "30", label: "30 min" },
-                    { value: "60", label: "1 hour" },
-                  ]}
-                />
-              </Row>
-            </Section>
-
-            <Section title="Notifications">
               <div className="space-y-3">
-                <Row label="Session warning">
+                <Row label="Auto-refresh">
                   <Select
-                    value={String(settings.notifySession)}
-                    onChange={(v) => updateSettings({ ...settings, notifySession: Number(v) })}
+                    value={String(settings.refreshInterval)}
+                    onChange={(v) => updateSettings({ ...settings, refreshInterval: Number(v) as AppSettings["refreshInterval"] })}
                     options={[
-                      { value: "0", label: "Off" },
-                      { value: "50", label: "50%" },
-                      { value: "70", label: "70%" },
-                      { value: "80", label: "80%" },
-                      { value: "90", label: "90%" },
+                      { value: "0", label: "Manual" },
+                      { value: "5", label: "5 min" },
+                      { value: "15", label: "15 min" },
+                      { value: "30", label: "30 min" },
+                      { value: "60", label: "1 hour" },
                     ]}
                   />
                 </Row>
-                <Row label="Weekly warning">
+                <Row label="Stale after">
                   <Select
-                    value={String(settings.notifyWeekly)}
-                    onChange={(v) => updateSettings({ ...settings, notifyWeekly: Number(v) })}
+                    value={String(settings.staleAfterIntervals)}
+                    onChange={(v) => updateSettings({ ...settings, staleAfterIntervals: Number(v) })}
                     options={[
-                      { value: "0", label: "Off" },
-                      { value: "50", label: "50%" },
-                      { value: "70", label: "70%" },
-                      { value: "80", label: "80%" },
-                      { value: "90", label: "90%" },
+                      { value: "2", label: "2 refreshes" },
+                      { value: "3", label: "3 refreshes" },
+                      { value: "5", label: "5 refreshes" },
                     ]}
                   />
                 </Row>
+              </div>
+            </Section>
+
+            <Section title="Notifications">
+              <div className="space-y-3">
+                {settings.notificationRules.map((rule, i) => (
+                  <RuleCard
+                    key={rule.id}
+                    rule={rule}
+                    onChange={(updated) => updateRule(i, updated)}
+                    onRemove={() => removeRule(i)}
+                  />
+                ))}
+                <button
+                  onClick={addRule}
+                  className="w-full py-2 text-[13px] text-gray-500 dark:text-gray-400 
+                    bg-black/[0.03] dark:bg-white/[0.06] hover:bg-black/[0.06] dark:hover:bg-white/[0.1] 
+                    rounded-lg transition-colors"
+                >
+                  Add Rule
+                </button>
                 <button
                   onClick={() => invoke("test_notification")}
                   className="w-full py-2 text-[13px] text-gray-500 dark:text-gray-400 
@@ -371,6 +858,150 @@ function App() {
                 </button>
               </div>
             </Section>
+
+            <Section title="Quiet Hours">
+              <div className="space-y-3">
+                <Row label="Enabled">
+                  <Toggle
+                    checked={settings.quietHours.enabled}
+                    onChange={(enabled) => updateQuietHours({ enabled })}
+                  />
+                </Row>
+                {settings.quietHours.enabled && (
+                  <>
+                    <Row label="Alerts during quiet hours">
+                      <Select
+                        value={settings.quietHours.mode}
+                        onChange={(v) => updateQuietHours({ mode: v as QuietSchedule["mode"] })}
+                        options={[
+                          { value: "digest", label: "Summarize afterwards" },
+                          { value: "drop", label: "Drop" },
+                        ]}
+                      />
+                    </Row>
+                    {settings.quietHours.periods.map((period, i) => (
+                      <div key={i} className="p-3 space-y-2 bg-black/[0.03] dark:bg-white/[0.06] rounded-lg">
+                        <div className="flex gap-1">
+                          {WEEKDAYS.map((day) => {
+                            const on = period.days.includes(day);
+                            return (
+                              <button
+                                key={day}
+                                onClick={() => updatePeriod(i, {
+                                  ...period,
+                                  days: on ? period.days.filter((d) => d !== day) : [...period.days, day],
+                                })}
+                                className={`flex-1 py-1 text-[11px] rounded-md transition-colors
+                                  ${on ? "bg-gray-900 text-white dark:bg-white dark:text-gray-900" : "text-gray-500 dark:text-gray-400"}`}
+                              >
+                                {day.slice(0, 2)}
+                              </button>
+                            );
+                          })}
+                        </div>
+                        <div className="flex items-center justify-between">
+                          <div className="flex items-center gap-2">
+                            <input
+                              type="time"
+                              value={period.start.slice(0, 5)}
+                              onChange={(e) => e.target.value && updatePeriod(i, { ...period, start: e.target.value })}
+                              className="px-2 py-1 text-[13px] rounded-lg bg-black/[0.03] dark:bg-white/[0.06]"
+                            />
+                            <span className="text-[12px] text-gray-400">–</span>
+                            <input
+                              type="time"
+                              value={period.end.slice(0, 5)}
+                              onChange={(e) => e.target.value && updatePeriod(i, { ...period, end: e.target.value })}
+                              className="px-2 py-1 text-[13px] rounded-lg bg-black/[0.03] dark:bg-white/[0.06]"
+                            />
+                          </div>
+                          <button
+                            onClick={() => updateQuietHours({ periods: settings.quietHours.periods.filter((_, j) => j !== i) })}
+                            className="text-[12px] text-rose-500 hover:text-rose-600"
+                          >
+                            Remove
+                          </button>
+                        </div>
+                      </div>
+                    ))}
+                    <button
+                      onClick={() => updateQuietHours({
+                        periods: [...settings.quietHours.periods, { days: ["Mon", "Tue", "Wed", "Thu", "Fri"], start: "22:00", end: "07:00" }],
+                      })}
+                      className="w-full py-2 text-[13px] text-gray-500 dark:text-gray-400 
+                        bg-black/[0.03] dark:bg-white/[0.06] hover:bg-black/[0.06] dark:hover:bg-white/[0.1] 
+                        rounded-lg transition-colors"
+                    >
+                      Add Period
+                    </button>
+                  </>
+                )}
+              </div>
+            </Section>
+
+            <Section title="Channels">
+              <div className="space-y-3">
+                {settings.channels.map((channel, i) => (
+                  <ChannelCard
+                    key={i}
+                    channel={channel}
+                    error={channelErrors[channel.name]}
+                    onChange={(updated) => updateChannel(i, updated)}
+                    onRemove={() => removeChannel(i)}
+                  />
+                ))}
+                <button
+                  onClick={addChannel}
+                  className="w-full py-2 text-[13px] text-gray-500 dark:text-gray-400 
+                    bg-black/[0.03] dark:bg-white/[0.06] hover:bg-black/[0.06] dark:hover:bg-white/[0.1] 
+                    rounded-lg transition-colors"
+                >
+                  Add Channel
+                </button>
+              </div>
+            </Section>
+
+            <Section title="Local API">
+              <div className="space-y-3">
+                <Row label="Enabled">
+                  <Toggle
+                    checked={settings.api.enabled}
+                    onChange={(enabled) => updateApi({ ...settings.api, enabled })}
+                  />
+                </Row>
+                {settings.api.enabled && (
+                  <>
+                    <Input label="Port" value={apiPort} onChange={setApiPort} onBlur={savePort} mono />
+                    <Row label="Prometheus metrics">
+                      <Toggle
+                        checked={settings.api.metrics}
+                        onChange={(metrics) => updateApi({ ...settings.api, metrics })}
+                      />
+                    </Row>
+                    {apiInfo?.url ? (
+                      <>
+                        <p className="text-[12px] text-gray-500 dark:text-gray-400">
+                          Listening on <span className="font-mono">{apiInfo.url}</span>
+                        </p>
+                        <Input label="Token" type="password" value={apiInfo.token} onChange={() => {}} mono />
+                        <button
+                          onClick={() => navigator.clipboard.writeText(apiInfo.token)}
+                          className="text-[12px] text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
+                        >
+                          Copy token
+                        </button>
+                      </>
+                    ) : (
+                      apiInfo && (
+                        <p className="text-[12px] text-rose-500">
+                          {apiInfo.error ?? `Couldn't listen on port ${settings.api.port}`}
+                        </p>
+                      )
+                    )}
+                  </>
+                )}
+              </div>
+            </Section>
           </div>
         )}
 
@@ -498,11 +1129,266 @@ function Toggle({ checked, onChange }: { checked: boolean; onChange: (value: boo
   );
 }
 
-function UsageBar({ label, pct, resetTime, color }: { 
+const defaultConditions: Record<RuleCondition["type"], RuleCondition> = {
+  crosses: { type: "crosses", percent: 80 },
+  escalating: { type: "escalating", percents: [50, 75, 90, 100] },
+  projectedLimit: { type: "projectedLimit", minutes: 60 },
+  reset: { type: "reset" },
+  rateAbove: { type: "rateAbove", percentPerHour: 20 },
+};
+
+function conditionValue(condition: RuleCondition): string | null {
+  switch (condition.type) {
+    case "crosses": return String(condition.percent);
+    case "escalating": return condition.percents.join(",");
+    case "projectedLimit": return String(condition.minutes);
+    case "rateAbove": return String(condition.percentPerHour);
+    case "reset": return null;
+  }
+}
+
+function withConditionValue(condition: RuleCondition, value: string): RuleCondition {
+  switch (condition.type) {
+    case "crosses": return { ...condition, percent: Number(value) };
+    case "escalating": return { ...condition, percents: value.split(",").map(Number) };
+    case "projectedLimit": return { ...condition, minutes: Number(value) };
+    case "rateAbove": return { ...condition, percentPerHour: Number(value) };
+    case "reset": return condition;
+  }
+}
+
+const conditionOptions: Record<RuleCondition["type"], { value: string; label: string }[]> = {
+  crosses: ["50", "70", "80", "90", "100"].map((v) => ({ value: v, label: `${v}%` })),
+  escalating: ["50,75,90,100", "75,90,100", "80,95"].map((v) => ({ value: v, label: v.replace(/,/g, " / ") + "%" })),
+  projectedLimit: [
+    { value: "30", label: "30 min" },
+    { value: "60", label: "1 hour" },
+    { value: "120", label: "2 hours" },
+    { value: "1440", label: "1 day" },
+  ],
+  rateAbove: ["10", "20", "30", "50"].map((v) => ({ value: v, label: `${v}%/h` })),
+  reset: [],
+};
+
+function RuleCard({ rule, onChange, onRemove }: {
+  rule: NotificationRule;
+  onChange: (rule: NotificationRule) => void;
+  onRemove: () => void;
+}) {
+  const [message, setMessage] = useState(rule.message);
+  const value = conditionValue(rule.condition);
+  const options = conditionOptions[rule.condition.type];
+  // Keep a custom value from settings.json selectable
+  const valueOptions = value && !options.some((o) => o.value === value) ? [...options, { value, label: value }] : options;
+
+  useEffect(() => setMessage(rule.message), [rule.message]);
+
+  return (
+    <div className="p-3 space-y-3 bg-black/[0.03] dark:bg-white/[0.06] rounded-lg">
+      <div className="flex items-center justify-between gap-2">
+        <div className="flex flex-wrap items-center gap-2">
+          <Select
+            value={rule.window}
+            onChange={(v) => onChange({ ...rule, window: v as NotificationRule["window"] })}
+            options={[
+              { value: "fiveHour", label: "Session" },
+              { value: "sevenDay", label: "Weekly" },
+            ]}
+          />
+          <Select
+            value={rule.condition.type}
+            onChange={(v) => onChange({ ...rule, condition: defaultConditions[v as RuleCondition["type"]] })}
+            options={[
+              { value: "crosses", label: "reaches" },
+              { value: "escalating", label: "reaches each of" },
+              { value: "projectedLimit", label: "limit within" },
+              { value: "rateAbove", label: "climbs faster than" },
+              { value: "reset", label: "resets" },
+            ]}
+          />
+          {value && (
+            <Select
+              value={value}
+              onChange={(v) => onChange({ ...rule, condition: withConditionValue(rule.condition, v) })}
+              options={valueOptions}
+            />
+          )}
+        </div>
+        <Toggle checked={rule.enabled} onChange={(enabled) => onChange({ ...rule, enabled })} />
+      </div>
+      <Row label="Repeat at most every">
+        <Select
+          value={String(rule.cooldownMinutes)}
+          onChange={(v) => onChange({ ...rule, cooldownMinutes: Number(v) })}
+          options={[
+            { value: "0", label: "No limit" },
+            { value: "30", label: "30 min" },
+            { value: "60", label: "1 hour" },
+            { value: "240", label: "4 hours" },
+            { value: "1440", label: "1 day" },
+          ]}
+        />
+      </Row>
+      <Row label="Quiet hours">
+        <div className="flex items-center gap-2">
+          {rule.quietHours && (
+            <>
+              <input
+                type="time"
+                value={rule.quietHours.start.slice(0, 5)}
+                onChange={(e) => e.target.value && onChange({ ...rule, quietHours: { ...rule.quietHours!, start: e.target.value } })}
+                className="px-2 py-1 text-[13px] rounded-lg bg-black/[0.03] dark:bg-white/[0.06]"
+              />
+              <span className="text-[12px] text-gray-400">–</span>
+              <input
+                type="time"
+                value={rule.quietHours.end.slice(0, 5)}
+                onChange={(e) => e.target.value && onChange({ ...rule, quietHours: { ...rule.quietHours!, end: e.target.value } })}
+                className="px-2 py-1 text-[13px] rounded-lg bg-black/[0.03] dark:bg-white/[0.06]"
+              />
+            </>
+          )}
+          <Toggle
+            checked={!!rule.quietHours}
+            onChange={(on) => onChange({ ...rule, quietHours: on ? { start: "22:00", end: "07:00" } : undefined })}
+          />
+        </div>
+      </Row>
+      <Input
+        label="Message ({window}, {pct}, {rate}, {limit}, {profile})"
+        value={message}
+        onChange={setMessage}
+        onBlur={() => message !== rule.message && onChange({ ...rule, message })}
+        placeholder="Default message"
+      />
+      <button onClick={onRemove} className="text-[12px] text-rose-500 hover:text-rose-600">
+        Remove rule
+      </button>
+    </div>
+  );
+}
+
+const channelPlaceholders: Record<ChannelKind, string> = {
+  webhook: "https://example.com/hooks/seekers",
+  slack: "https://hooks.slack.com/services/...",
+  discord: "https://discord.com/api/webhooks/...",
+  ntfy: "https://ntfy.sh/my-topic",
+  gotify: "https://gotify.example.com/message",
+};
+
+function ChannelCard({ channel, error, onChange, onRemove }: {
+  channel: ChannelConfig;
+  error?: string;
+  onChange: (channel: ChannelConfig) => void;
+  onRemove: () => void;
+}) {
+  const [draft, setDraft] = useState(channel);
+  const [test, setTest] = useState<"idle" | "sending" | "sent" | string>("idle");
+
+  useEffect(() => setDraft(channel), [channel]);
+
+  function commit() {
+    if (JSON.stringify(draft) !== JSON.stringify(channel)) onChange({ ...draft, token: draft.token || undefined });
+  }
+
+  async function sendTest() {
+    setTest("sending");
+    try {
+      await invoke("test_channel", { channel: draft });
+      setTest("sent");
+    } catch (e) {
+      setTest(String(e));
+    }
+  }
+
+  const needsToken = channel.kind === "ntfy" || channel.kind === "gotify";
+  // A failed test is more recent than the last delivery error
+  const shownError = ["idle", "sending", "sent"].includes(test) ? error : test;
+
+  return (
+    <div className="p-3 space-y-3 bg-black/[0.03] dark:bg-white/[0.06] rounded-lg">
+      <div className="flex items-center justify-between gap-2">
+        <Select
+          value={channel.kind}
+          onChange={(v) => onChange({ ...channel, kind: v as ChannelKind })}
+          options={[
+            { value: "webhook", label: "Webhook (JSON)" },
+            { value: "slack", label: "Slack" },
+            { value: "discord", label: "Discord" },
+            { value: "ntfy", label: "ntfy" },
+            { value: "gotify", label: "Gotify" },
+          ]}
+        />
+        <Toggle checked={channel.enabled} onChange={(enabled) => onChange({ ...channel, enabled })} />
+      </div>
+      <Input label="Name" value={draft.name} onChange={(name) => setDraft({ ...draft, name })} onBlur={commit} />
+      <Input
+        label="URL"
+        value={draft.url}
+        onChange={(url) => setDraft({ ...draft, url })}
+        onBlur={commit}
+        placeholder={channelPlaceholders[channel.kind]}
+        mono
+      />
+      {needsToken && (
+        <Input
+          label={channel.kind === "gotify" ? "Application token" : "Access token (optional)"}
+          type="password"
+          value={draft.token ?? ""}
+          onChange={(token) => setDraft({ ...draft, token })}
+          onBlur={commit}
+          mono
+        />
+      )}
+      <Input
+        label="Title ({title}, {body})"
+        value={draft.title}
+        onChange={(title) => setDraft({ ...draft, title })}
+        onBlur={commit}
+        placeholder="Alert title"
+      />
+      <Input
+        label="Message ({title}, {body})"
+        value={draft.message}
+        onChange={(message) => setDraft({ ...draft, message })}
+        onBlur={commit}
+        placeholder="Alert message"
+      />
+      <Row label="Retries">
+        <Select
+          value={String(channel.retries)}
+          onChange={(v) => onChange({ ...channel, retries: Number(v) })}
+          options={[
+            { value: "0", label: "None" },
+            { value: "1", label: "1" },
+            { value: "3", label: "3" },
+            { value: "5", label: "5" },
+          ]}
+        />
+      </Row>
+      {shownError && <p className="text-[12px] text-rose-500">{shownError}</p>}
+      <div className="flex items-center justify-between">
+        <button
+          onClick={sendTest}
+          disabled={!draft.url || test === "sending"}
+          className="text-[12px] text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200 disabled:opacity-50"
+        >
+          {test === "sending" ? "Sending…" : test === "sent" ? "Sent ✓" : "Send test"}
+        </button>
+        <button onClick={onRemove} className="text-[12px] text-rose-500 hover:text-rose-600">
+          Remove channel
+        </button>
+      </div>
+    </div>
+  );
+}
+
+function UsageBar({ label, pct, resetTime, color, warning }: { 
   label: string; 
   pct: number; 
   resetTime: string;
   color: string;
+  warning?: string;
 }) {
   return (
     <div>
@@ -521,6 +1407,7 @@ function UsageBar({ label, pct, resetTime, color }: {
           style={{ width: `${Math.min(pct, 100)}%` }}
         />
       </div>
+      {warning && <p className="mt-1 text-[11px] text-rose-500">{warning}</p>}
     </div>
   );
 }